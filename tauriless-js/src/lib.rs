@@ -1,9 +1,17 @@
 mod utils;
 
 use js_sys::Uint8Array;
-use tauriless_common::url::command_to_url;
+use std::cell::{Cell, RefCell};
+
+use tauriless_common::auth::{AUTH_TOKENS_GLOBAL, AUTH_TOKEN_HEADER};
+use tauriless_common::status::{COMMAND_ERROR, COMMAND_TIMEOUT};
+use tauriless_common::url::{
+    cancellation_url_at, command_to_url_at, command_to_url_with_invocation_id_at, UrlScheme,
+    BASE_URLS_GLOBAL, TAURILESS_PROTOCOL,
+};
+use tauriless_serde::Limits;
 use wasm_bindgen::prelude::*;
-use web_sys::XmlHttpRequest;
+use web_sys::{AbortSignal, DomException, XmlHttpRequest};
 
 #[wasm_bindgen(start)]
 fn start() {
@@ -27,15 +35,267 @@ pub fn encode(args: JsValue) -> Result<JsValue, JsValue> {
         .map_err(|_| JsValue::from_str("Failed to convert the endcoded args array to JsValue"))
 }
 
+thread_local! {
+    // The ids of the invocations must be unique among the invocations in progress. The random prefix
+    // distinguishes the invocations made before and after the page is reloaded.
+    static INVOCATION_ID_PREFIX: u32 = (js_sys::Math::random() * f64::from(u32::MAX)) as u32;
+    static NEXT_INVOCATION_ID: Cell<u64> = const { Cell::new(0) };
+    static BASE_URL: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// This function sets the base URL the commands are invoked at, e.g. `http://127.0.0.1:3001`
+/// of the `tauriless` dev server when the frontend runs in a regular browser during development.
+///
+/// Without the base URL, or after it's reset with `undefined`, the commands are invoked on the core process
+/// of `wry` via the custom protocol. The URL of the custom protocol is defined by the core process or,
+/// failing that, detected from the platform.
+#[wasm_bindgen(js_name = setBaseUrl)]
+pub fn set_base_url(base_url: Option<String>) {
+    BASE_URL.with(|url| *url.borrow_mut() = base_url);
+}
+
+// Where the requests are sent, the auth token they carry, if any, and the limits of their arguments.
+#[derive(Clone)]
+struct Target {
+    base_url: String,
+    auth_token: Option<String>,
+    limits: Limits,
+}
+
+impl Target {
+    fn new(protocol: &str, base_url: Option<String>) -> Self {
+        Self {
+            base_url: base_url.unwrap_or_else(|| native_base_url(protocol)),
+            auth_token: injected(AUTH_TOKENS_GLOBAL, protocol),
+            limits: Limits::default(),
+        }
+    }
+
+    fn open(&self, xhr: &XmlHttpRequest, url: &str) {
+        xhr.open_with_async("POST", url, true).unwrap();
+        if let Some(auth_token) = &self.auth_token {
+            xhr.set_request_header(AUTH_TOKEN_HEADER, auth_token)
+                .unwrap();
+        }
+    }
+}
+
+// Returns the value for the protocol from the global object defined by the core process, if any.
+fn injected(global: &str, protocol: &str) -> Option<String> {
+    js_sys::Reflect::get(&js_sys::global(), &JsValue::from_str(global))
+        .ok()
+        .filter(|values| values.is_object())
+        .and_then(|values| js_sys::Reflect::get(&values, &JsValue::from_str(protocol)).ok())
+        .and_then(|value| value.as_string())
+}
+
+// The base URL defined by the core process takes precedence over the detected one.
+fn native_base_url(protocol: &str) -> String {
+    injected(BASE_URLS_GLOBAL, protocol).unwrap_or_else(|| detect_url_scheme().base_url(protocol))
+}
+
+/// The client invoking the commands served by the custom protocol with the given name, which is created
+/// by `createClient`.
+///
+/// The WebViews with different command sets, or the libraries registering their own commands, use different
+/// protocols, which are set with `Config::with_protocol` on the core process.
+#[wasm_bindgen]
+pub struct Client {
+    scheme: String,
+    base_url: Option<String>,
+    auth_token: Option<String>,
+    limits: Limits,
+}
+
+#[wasm_bindgen]
+impl Client {
+    /// The name of the custom protocol serving the commands.
+    #[wasm_bindgen(getter)]
+    pub fn scheme(&self) -> String {
+        self.scheme.clone()
+    }
+
+    /// Same as the `invoke` function but invokes the command served by the protocol of the client.
+    pub fn invoke(
+        &self,
+        command: &str,
+        args: JsValue,
+        signal: Option<AbortSignal>,
+    ) -> Result<js_sys::Promise, JsValue> {
+        let target = Target::new(&self.scheme, self.base_url.clone());
+        let target = Target {
+            // The token passed to `createClient` takes precedence over the injected one.
+            auth_token: self.auth_token.clone().or(target.auth_token),
+            limits: self.limits,
+            ..target
+        };
+        invoke_at(target, command, args, signal)
+    }
+}
+
+/// This function creates a `Client` from the options object with the optional properties
+///
+/// * `scheme`, the name of the custom protocol serving the commands, `"tauiriless"` by default;
+/// * `baseUrl`, the base URL the commands are invoked at instead, e.g. of the `tauriless` dev server;
+/// * `authToken`, the secret of `Security::with_auth_token` for the frontends the core process doesn't inject
+///   it into, e.g. the ones served by a dev server or opened in an external browser;
+/// * `maxBodySize`, `maxDepth` and `maxCollectionLength`, the limits of the arguments set with `Config::with_limits`
+///   on the core process, if they differ from the default ones.
+#[wasm_bindgen(js_name = createClient)]
+pub fn create_client(options: Option<js_sys::Object>) -> Result<Client, JsValue> {
+    let option = |name: &str| -> Result<Option<JsValue>, JsValue> {
+        let Some(options) = &options else {
+            return Ok(None);
+        };
+        let value: JsValue = js_sys::Reflect::get(options, &JsValue::from_str(name))?;
+        Ok(Some(value).filter(|value| !value.is_undefined()))
+    };
+    let string_option = |name: &str| -> Result<Option<String>, JsValue> {
+        option(name)?
+            .map(|value| {
+                value.as_string().ok_or_else(|| {
+                    JsValue::from_str(&format!("The `{name}` option must be a string"))
+                })
+            })
+            .transpose()
+    };
+    let limit_option = |name: &str, default: usize| -> Result<usize, JsValue> {
+        let Some(value) = option(name)? else {
+            return Ok(default);
+        };
+        match value.as_f64() {
+            Some(limit) if limit >= 0.0 && limit.fract() == 0.0 => Ok(limit as usize),
+            _ => Err(JsValue::from_str(&format!(
+                "The `{name}` option must be a non-negative integer"
+            ))),
+        }
+    };
+    let defaults = Limits::default();
+    Ok(Client {
+        scheme: string_option("scheme")?.unwrap_or_else(|| TAURILESS_PROTOCOL.to_string()),
+        base_url: string_option("baseUrl")?,
+        auth_token: string_option("authToken")?,
+        limits: Limits {
+            max_body_size: limit_option("maxBodySize", defaults.max_body_size)?,
+            max_depth: limit_option("maxDepth", defaults.max_depth)?,
+            max_collection_len: limit_option("maxCollectionLength", defaults.max_collection_len)?,
+        },
+    })
+}
+
+// WebView2 exposes the custom protocols as `http://<protocol>.localhost/`, while WebKitGTK and WKWebView
+// expose them as `<protocol>://localhost/`.
+fn detect_url_scheme() -> UrlScheme {
+    let Some(window) = web_sys::window() else {
+        return UrlScheme::Custom;
+    };
+    let location = window.location();
+    let protocol: String = location.protocol().unwrap_or_default();
+    let hostname: String = location.hostname().unwrap_or_default();
+    // The page itself may be served by a custom protocol, e.g. with the embedded assets.
+    if protocol != "http:" && protocol != "https:" {
+        return UrlScheme::Custom;
+    }
+    if hostname.ends_with(".localhost") {
+        return UrlScheme::Http;
+    }
+    let user_agent: String = window.navigator().user_agent().unwrap_or_default();
+    if user_agent.contains("Windows") {
+        UrlScheme::Http
+    } else {
+        UrlScheme::Custom
+    }
+}
+
+fn next_invocation_id() -> String {
+    let prefix: u32 = INVOCATION_ID_PREFIX.with(|prefix| *prefix);
+    let id: u64 = NEXT_INVOCATION_ID.with(|next| {
+        let id = next.get();
+        next.set(id.wrapping_add(1));
+        id
+    });
+    format!("{prefix:x}-{id:x}")
+}
+
+// Asks the core process to cancel the invocation. The response is not awaited.
+fn send_cancellation(target: &Target, invocation_id: &str) {
+    let xhr = XmlHttpRequest::new().unwrap();
+    target.open(&xhr, &cancellation_url_at(&target.base_url, invocation_id));
+    xhr.send().unwrap();
+}
+
+// Rejects the promise with a `DOMException` named `NetworkError` or `TimeoutError` when the request fails
+// without a response.
+fn reject_on_failure(xhr: &XmlHttpRequest, command: &str, reject: &js_sys::Function) {
+    let rejection = |name: &'static str, reason: &str| {
+        let message = format!("The request invoking `{command}` {reason}");
+        let reject: js_sys::Function = reject.clone();
+        wasm_bindgen::closure::Closure::once(move |_event: web_sys::Event| {
+            let error = DomException::new_with_message_and_name(&message, name).unwrap();
+            reject.call1(&JsValue::UNDEFINED, &error).unwrap();
+        })
+    };
+    let handler = rejection("NetworkError", "failed");
+    xhr.set_onerror(Some(handler.as_ref().unchecked_ref()));
+    handler.forget();
+    let handler = rejection("TimeoutError", "timed out");
+    xhr.set_ontimeout(Some(handler.as_ref().unchecked_ref()));
+    handler.forget();
+}
+
 /// This function allows you to invoke a `tauriless` command on the core process of `wry`
 /// from the WebView process.
+///
+/// The returned promise is resolved with the return value of the command. If the command returns
+/// `Result<T, E>`, the promise is resolved with the `Ok` value or rejected with the `Err` value.
+///
+/// If the optional `signal` is aborted before the command completes, the promise is rejected with
+/// the [`reason`](https://developer.mozilla.org/en-US/docs/Web/API/AbortSignal/reason) of the signal
+/// and the command is cancelled on the core process.
+///
+/// If the command doesn't complete before its timeout, the promise is rejected with a
+/// [`DOMException`](https://developer.mozilla.org/en-US/docs/Web/API/DOMException) named `TimeoutError`.
+///
+/// If the request fails without a response, e.g. because the core process rejected it without allowing
+/// the page to read the rejection, the promise is rejected with a `DOMException` named `NetworkError`.
 #[wasm_bindgen]
-pub fn invoke(command: &str, args: JsValue) -> Result<js_sys::Promise, JsValue> {
-    let encoded: Vec<u8> = tauriless_serde::js_value_to_vec_u8(args)?;
+pub fn invoke(
+    command: &str,
+    args: JsValue,
+    signal: Option<AbortSignal>,
+) -> Result<js_sys::Promise, JsValue> {
+    let base_url: Option<String> = BASE_URL.with(|url| url.borrow().clone());
+    invoke_at(
+        Target::new(TAURILESS_PROTOCOL, base_url),
+        command,
+        args,
+        signal,
+    )
+}
+
+fn invoke_at(
+    target: Target,
+    command: &str,
+    args: JsValue,
+    signal: Option<AbortSignal>,
+) -> Result<js_sys::Promise, JsValue> {
+    let encoded: Vec<u8> = tauriless_serde::js_value_to_vec_u8_with_limits(args, &target.limits)?;
 
     let promise = js_sys::Promise::new(&mut move |resolve, reject| {
+        if let Some(signal) = &signal {
+            if signal.aborted() {
+                reject.call1(&JsValue::UNDEFINED, &signal.reason()).unwrap();
+                return;
+            }
+        }
         let xhr = XmlHttpRequest::new().unwrap();
+        // The promise is settled on the `load`, `error` or `timeout` event of the request or on the `abort`
+        // event of the signal.
+        let abort_reject: js_sys::Function = reject.clone();
         xhr.set_response_type(web_sys::XmlHttpRequestResponseType::Arraybuffer);
+        // The `load` event isn't dispatched when the response can't be read, e.g. when the core process
+        // is unreachable or the browser blocks the response.
+        reject_on_failure(&xhr, command, &reject);
         {
             let handler = wasm_bindgen::closure::Closure::once(move |event: web_sys::Event| {
                 let xhr = event
@@ -43,7 +303,10 @@ pub fn invoke(command: &str, args: JsValue) -> Result<js_sys::Promise, JsValue>
                     .unwrap()
                     .dyn_into::<XmlHttpRequest>()
                     .unwrap();
-                if xhr.status().unwrap() == 200 {
+                let status: u16 = xhr.status().unwrap();
+                // The body of the response for a command that returned `Err` is the serialized error value,
+                // so it's decoded just like the body of the successful response.
+                if status == 200 || status == COMMAND_ERROR {
                     let response = xhr
                         .response()
                         .unwrap()
@@ -60,7 +323,23 @@ pub fn invoke(command: &str, args: JsValue) -> Result<js_sys::Promise, JsValue>
                             return;
                         }
                     };
-                    resolve.call1(&JsValue::UNDEFINED, &response).unwrap();
+                    if status == 200 {
+                        resolve.call1(&JsValue::UNDEFINED, &response).unwrap();
+                    } else {
+                        reject.call1(&JsValue::UNDEFINED, &response).unwrap();
+                    }
+                } else if status == COMMAND_TIMEOUT {
+                    // The body of the response is the human-readable message.
+                    let response = xhr
+                        .response()
+                        .unwrap()
+                        .dyn_into::<js_sys::ArrayBuffer>()
+                        .unwrap();
+                    let message: String =
+                        String::from_utf8_lossy(&Uint8Array::new(&response).to_vec()).into_owned();
+                    let error =
+                        DomException::new_with_message_and_name(&message, "TimeoutError").unwrap();
+                    reject.call1(&JsValue::UNDEFINED, &error).unwrap();
                 } else {
                     reject
                         .call1(&JsValue::UNDEFINED, &xhr.response().unwrap())
@@ -70,8 +349,58 @@ pub fn invoke(command: &str, args: JsValue) -> Result<js_sys::Promise, JsValue>
             xhr.set_onload(Some(handler.as_ref().unchecked_ref()));
             handler.forget();
         }
-        let url: String = command_to_url(command);
-        xhr.open_with_async("POST", &url, true).unwrap();
+        let url: String = match &signal {
+            Some(signal) => {
+                let invocation_id: String = next_invocation_id();
+                let url: String =
+                    command_to_url_with_invocation_id_at(&target.base_url, command, &invocation_id);
+                let handler = {
+                    let xhr = xhr.clone();
+                    let target: Target = target.clone();
+                    let reject = abort_reject;
+                    wasm_bindgen::closure::Closure::once(move |event: web_sys::Event| {
+                        // The response may have already been received.
+                        if xhr.ready_state() == XmlHttpRequest::DONE {
+                            return;
+                        }
+                        // The `load` event won't be dispatched for the aborted request.
+                        xhr.abort().unwrap();
+                        // The cancellation is sent to where the invocation was sent.
+                        send_cancellation(&target, &invocation_id);
+                        let signal = event.target().unwrap().dyn_into::<AbortSignal>().unwrap();
+                        reject.call1(&JsValue::UNDEFINED, &signal.reason()).unwrap();
+                    })
+                };
+                let options = web_sys::AddEventListenerOptions::new();
+                options.set_once(true);
+                signal
+                    .add_event_listener_with_callback_and_add_event_listener_options(
+                        "abort",
+                        handler.as_ref().unchecked_ref(),
+                        &options,
+                    )
+                    .unwrap();
+                // The listener is removed and dropped once the request is settled, so that a signal reused
+                // across the invocations doesn't collect them. The `loadend` event follows the `load`, `error`,
+                // `timeout` and `abort` events alike.
+                let signal: AbortSignal = signal.clone();
+                let cleanup =
+                    wasm_bindgen::closure::Closure::once(move |_event: web_sys::Event| {
+                        signal
+                            .remove_event_listener_with_callback(
+                                "abort",
+                                handler.as_ref().unchecked_ref(),
+                            )
+                            .unwrap();
+                        drop(handler);
+                    });
+                xhr.set_onloadend(Some(cleanup.as_ref().unchecked_ref()));
+                cleanup.forget();
+                url
+            }
+            None => command_to_url_at(&target.base_url, command),
+        };
+        target.open(&xhr, &url);
         xhr.send_with_opt_u8_array(Some(&encoded)).unwrap();
     });
 
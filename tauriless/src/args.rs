@@ -0,0 +1,20 @@
+use crate::Command;
+
+/// Deserializes the arguments of the command supplied either positionally or by parameter name.
+///
+/// See [`tauriless_serde::slice_to_deserialize_args()`] for the conventions.
+#[doc(hidden)]
+pub fn deserialize_args<C: Command>(
+    body: &[u8],
+) -> Result<C::Args, tauriless_serde::slice_to_deserialize_args::Error> {
+    use tauriless_serde::PositionalOrNamed;
+
+    let args = match tauriless_serde::slice_to_deserialize_args::<C::PositionalArgs, C::NamedArgs>(
+        body,
+        C::MIN_ARITY..=C::ARITY,
+    )? {
+        PositionalOrNamed::Positional(positional_args) => C::from_positional_args(positional_args),
+        PositionalOrNamed::Named(named_args) => C::from_named_args(named_args),
+    };
+    Ok(args)
+}
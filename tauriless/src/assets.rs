@@ -0,0 +1,168 @@
+//! The custom protocol serving the frontend embedded into the binary with [`RustEmbed`], so the application
+//! doesn't need a TCP listener reachable by the other local processes.
+//!
+//! [`WebViewBuilderExt::with_tauriless_assets`](crate::WebViewBuilderExt::with_tauriless_assets) registers
+//! the protocol, and the WebView loads the frontend from [`ASSETS_URL`]. The assets are served with
+//!
+//! * the `Content-Type` detected from the extension of the file;
+//! * the fallback to `index.html` for the paths without an extension, e.g. the routes of a single-page application;
+//! * the `ETag` derived from the hash of the file, answering the matching `If-None-Match` with `304 Not Modified`;
+//! * the precompressed variants, e.g. `app.js.br` or `app.js.gz` next to `app.js`, when the request accepts them.
+//!
+//! Available with the `assets` feature. The [`RustEmbed`] derive macro comes from the `rust-embed` crate, which
+//! the application depends on directly.
+//!
+//! ## Example
+//!
+//! ```rust,ignore
+//! use rust_embed::RustEmbed;
+//! use tauriless::WebViewBuilderExt;
+//!
+//! #[derive(RustEmbed)]
+//! #[folder = "front/dist"]
+//! struct Assets;
+//!
+//! let webview = WebViewBuilder::new(&window)
+//!     .with_tauriless_assets::<Assets>()
+//!     .with_tauriless_commands(commands!(add))
+//!     .with_url(tauriless::assets::ASSETS_URL)
+//!     .build()?;
+//! ```
+
+use std::borrow::Cow;
+
+use rust_embed::{EmbeddedFile, RustEmbed};
+use wry::http::{header, HeaderValue, Method, Request, Response, StatusCode};
+
+use crate::router::percent_decode;
+
+/// The name of the custom protocol serving the assets.
+pub const ASSETS_PROTOCOL: &str = "tauriless-assets";
+
+/// The URL of `index.html` served by the custom protocol, which the WebView should load.
+///
+/// On Windows, the custom protocols are exposed by WebView2 as `http://<protocol>.localhost/`.
+#[cfg(windows)]
+pub const ASSETS_URL: &str = "http://tauriless-assets.localhost/";
+/// The URL of `index.html` served by the custom protocol, which the WebView should load.
+///
+/// On Windows, the custom protocols are exposed by WebView2 as `http://<protocol>.localhost/`.
+#[cfg(not(windows))]
+pub const ASSETS_URL: &str = "tauriless-assets://localhost/";
+
+const INDEX: &str = "index.html";
+
+// The precompressed variants in the order of preference.
+const ENCODINGS: [(&str, &str); 2] = [("br", ".br"), ("gzip", ".gz")];
+
+/// Serves the asset of `A` at the path of the URL of the request.
+///
+/// This is the handler of the custom protocol registered by
+/// [`WebViewBuilderExt::with_tauriless_assets`](crate::WebViewBuilderExt::with_tauriless_assets), which can
+/// also be mounted in other hosts.
+pub fn serve<A: RustEmbed>(request: &Request<Vec<u8>>) -> Response<Cow<'static, [u8]>> {
+    let method: &Method = request.method();
+    if method != Method::GET && method != Method::HEAD {
+        return empty(StatusCode::METHOD_NOT_ALLOWED);
+    }
+    let path: Cow<'_, str> = percent_decode(request.uri().path().trim_start_matches('/'));
+    let path: &str = match path.as_ref() {
+        "" => INDEX,
+        path => path,
+    };
+    let path: &str = if A::get(path).is_some() || has_extension(path) {
+        path
+    } else {
+        INDEX
+    };
+    let Some(file) = A::get(path) else {
+        return empty(StatusCode::NOT_FOUND);
+    };
+
+    // The MIME type of the original file rather than of its precompressed variant.
+    let mimetype: HeaderValue = HeaderValue::from_str(file.metadata.mimetype())
+        .unwrap_or(HeaderValue::from_static("application/octet-stream"));
+
+    let accepted_encodings: &str = request
+        .headers()
+        .get(header::ACCEPT_ENCODING)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or("");
+    let (file, encoding): (EmbeddedFile, Option<&str>) = ENCODINGS
+        .iter()
+        .filter(|(encoding, _)| accepts(accepted_encodings, encoding))
+        .find_map(|(encoding, suffix)| {
+            A::get(&format!("{path}{suffix}")).map(|compressed| (compressed, Some(*encoding)))
+        })
+        .unwrap_or((file, None));
+
+    let etag: String = etag(&file);
+    let mut response = Response::builder()
+        .header(header::CONTENT_TYPE, mimetype)
+        .header(header::ETAG, &etag)
+        .header(header::CACHE_CONTROL, "no-cache")
+        .header(header::VARY, "accept-encoding");
+    if let Some(encoding) = encoding {
+        response = response.header(header::CONTENT_ENCODING, encoding);
+    }
+
+    let not_modified: bool = request
+        .headers()
+        .get(header::IF_NONE_MATCH)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|tags| {
+            tags.split(',')
+                .map(|tag| tag.trim().trim_start_matches("W/"))
+                .any(|tag| tag == etag || tag == "*")
+        });
+    if not_modified {
+        return response
+            .status(StatusCode::NOT_MODIFIED)
+            .body(Cow::Borrowed(&[][..]))
+            .unwrap();
+    }
+    let body: Cow<'static, [u8]> = if method == Method::HEAD {
+        Cow::Borrowed(&[])
+    } else {
+        file.data
+    };
+    response.status(StatusCode::OK).body(body).unwrap()
+}
+
+fn empty(status: StatusCode) -> Response<Cow<'static, [u8]>> {
+    Response::builder()
+        .status(status)
+        .body(Cow::Borrowed(&[][..]))
+        .unwrap()
+}
+
+fn has_extension(path: &str) -> bool {
+    path.rsplit('/')
+        .next()
+        .is_some_and(|file_name| file_name.contains('.'))
+}
+
+// Whether the value of `Accept-Encoding` accepts the encoding, ignoring the weights other than `q=0`.
+fn accepts(accepted_encodings: &str, encoding: &str) -> bool {
+    accepted_encodings.split(',').any(|accepted| {
+        let mut params = accepted.split(';').map(str::trim);
+        let name: &str = params.next().unwrap_or("");
+        let rejected: bool = params.any(|param| {
+            param
+                .strip_prefix("q=")
+                .and_then(|q| q.parse::<f32>().ok())
+                .is_some_and(|q| q == 0.0)
+        });
+        (name.eq_ignore_ascii_case(encoding) || name == "*") && !rejected
+    })
+}
+
+fn etag(file: &EmbeddedFile) -> String {
+    let hash: String = file
+        .metadata
+        .sha256_hash()
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect();
+    format!("\"{hash}\"")
+}
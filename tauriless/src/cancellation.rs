@@ -0,0 +1,207 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+
+use crate::Command;
+
+/// The argument type for the commands that can be cancelled cooperatively.
+///
+/// The arguments of this type are not supplied by the frontend. The token is cancelled when the
+/// [`AbortSignal`](https://developer.mozilla.org/en-US/docs/Web/API/AbortSignal) passed to `invoke` from
+/// `tauriless-js` is aborted.
+///
+/// The future of an asynchronous command that doesn't take a [`CancellationToken`] is dropped on cancellation.
+/// The command that takes one is responsible for observing it, so it can clean up before returning.
+///
+/// ## Example
+///
+/// ```rust
+/// use tauriless::{command, CancellationToken};
+///
+/// #[command]
+/// async fn count(to: u32, token: CancellationToken) -> Option<u32> {
+///     for i in 0..to {
+///         if token.is_cancelled() {
+///             // clean up
+///             return None;
+///         }
+///         // some async code
+///     }
+///     Some(to)
+/// }
+/// ```
+#[derive(Clone, Default)]
+pub struct CancellationToken {
+    inner: Arc<Inner>,
+}
+
+#[derive(Default)]
+struct Inner {
+    cancelled: AtomicBool,
+    wakers: Mutex<Vec<Waker>>,
+}
+
+impl CancellationToken {
+    /// Creates a [`CancellationToken`] that is not cancelled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancels the token and wakes up the tasks awaiting [`CancellationToken::cancelled`].
+    pub fn cancel(&self) {
+        if self.inner.cancelled.swap(true, Ordering::AcqRel) {
+            return;
+        }
+        let wakers: Vec<Waker> = std::mem::take(&mut *self.inner.wakers.lock().unwrap());
+        for waker in wakers {
+            waker.wake();
+        }
+    }
+
+    /// Returns `true` if the token was cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.inner.cancelled.load(Ordering::Acquire)
+    }
+
+    /// Returns the future that is completed when the token is cancelled.
+    pub fn cancelled(&self) -> Cancelled<'_> {
+        Cancelled { token: self }
+    }
+}
+
+impl std::fmt::Debug for CancellationToken {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CancellationToken")
+            .field("is_cancelled", &self.is_cancelled())
+            .finish()
+    }
+}
+
+/// The future returned by [`CancellationToken::cancelled`].
+pub struct Cancelled<'a> {
+    token: &'a CancellationToken,
+}
+
+impl Future for Cancelled<'_> {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if self.token.is_cancelled() {
+            return Poll::Ready(());
+        }
+        let mut wakers = self.token.inner.wakers.lock().unwrap();
+        // `cancel` sets the flag before taking the lock, so checking it again under the lock
+        // guarantees that the waker is either woken up or not needed.
+        if self.token.is_cancelled() {
+            return Poll::Ready(());
+        }
+        if !wakers.iter().any(|waker| waker.will_wake(cx.waker())) {
+            wakers.push(cx.waker().clone());
+        }
+        Poll::Pending
+    }
+}
+
+/// The tokens of the invocations that are in progress and can be cancelled, keyed by invocation id.
+///
+/// The clones share the tokens, so the invocations of the commands of the plugins can be cancelled as well.
+#[derive(Clone, Default)]
+pub(crate) struct Invocations {
+    tokens: Arc<Mutex<HashMap<String, CancellationToken>>>,
+}
+
+impl Invocations {
+    /// Registers the invocation with the given id, if any, so that it can be cancelled with
+    /// [`Invocations::cancel`] until the returned [`Invocation`] is dropped.
+    pub(crate) fn register(&self, id: Option<&str>) -> Invocation {
+        let token = CancellationToken::new();
+        let id: Option<String> = id.map(|id| {
+            let mut tokens = self.tokens.lock().unwrap();
+            tokens.insert(id.to_string(), token.clone());
+            id.to_string()
+        });
+        Invocation {
+            id,
+            token,
+            tokens: self.tokens.clone(),
+        }
+    }
+
+    /// Cancels the invocation with the given id. Returns `false` if there's no such invocation in progress.
+    pub(crate) fn cancel(&self, id: &str) -> bool {
+        let token: Option<CancellationToken> = self.tokens.lock().unwrap().remove(id);
+        match token {
+            Some(token) => {
+                token.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// The invocation of a command that is in progress.
+#[doc(hidden)]
+pub struct Invocation {
+    id: Option<String>,
+    token: CancellationToken,
+    tokens: Arc<Mutex<HashMap<String, CancellationToken>>>,
+}
+
+impl Invocation {
+    pub fn token(&self) -> &CancellationToken {
+        &self.token
+    }
+}
+
+impl Drop for Invocation {
+    fn drop(&mut self) {
+        if let Some(id) = self.id.take() {
+            let mut tokens = self.tokens.lock().unwrap();
+            // The id could have been reused by another invocation once this one was cancelled.
+            if tokens
+                .get(&id)
+                .is_some_and(|token| Arc::ptr_eq(&token.inner, &self.token.inner))
+            {
+                tokens.remove(&id);
+            }
+        }
+    }
+}
+
+/// Returns the id of the invocation from the query of the URL, if any.
+#[doc(hidden)]
+pub fn invocation_id(uri: &wry::http::Uri) -> Option<&str> {
+    uri.query()?.split('&').find_map(|pair| {
+        let (key, value) = pair.split_once('=')?;
+        (key == tauriless_common::url::INVOCATION_ID_PARAM).then_some(value)
+    })
+}
+
+/// Awaits the future of the command unless the token is cancelled first, in which case the future is dropped
+/// and `None` is returned.
+///
+/// The futures of the commands that take a [`CancellationToken`] are never dropped since they're
+/// expected to observe the cancellation themselves.
+#[doc(hidden)]
+pub async fn cancellable<C, F>(token: &CancellationToken, future: F) -> Option<F::Output>
+where
+    C: Command,
+    F: Future,
+{
+    if C::OBSERVES_CANCELLATION {
+        return Some(future.await);
+    }
+    let mut future: Pin<Box<F>> = Box::pin(future);
+    let mut cancelled: Cancelled<'_> = token.cancelled();
+    std::future::poll_fn(move |cx| {
+        if Pin::new(&mut cancelled).poll(cx).is_ready() {
+            return Poll::Ready(None);
+        }
+        future.as_mut().poll(cx).map(Some)
+    })
+    .await
+}
@@ -0,0 +1,342 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use wry::http::{header, HeaderMap};
+
+/// A named group of the commands, optionally with the scopes of their arguments, granted to the pages
+/// whose URLs match its patterns.
+///
+/// The names of the commands and the patterns may contain `*`, which matches any sequence of characters,
+/// e.g. `plugin:fs|*` matches all the commands of the plugin `fs`. The underscores in the names of the
+/// commands are equivalent to hyphens, just like in the URLs of the commands.
+///
+/// ## Example
+///
+/// ```rust
+/// use tauriless::{Capability, Scope};
+///
+/// let viewer = Capability::new("viewer")
+///     .with_url("http://tauriless-assets.localhost/viewer/*")
+///     .with_command("get_document")
+///     .with_command("plugin:fs|read")
+///     .with_scope("plugin:fs|read", "path", Scope::new().with_allowed("docs/*"));
+/// ```
+#[derive(Clone, Debug, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Capability {
+    name: String,
+    #[serde(default)]
+    commands: Vec<String>,
+    #[serde(default)]
+    urls: Vec<String>,
+    // The scopes of the arguments keyed by the name of the command and then by the name of the argument.
+    #[serde(default)]
+    scopes: HashMap<String, HashMap<String, Scope>>,
+}
+
+impl Capability {
+    /// Creates the [`Capability`] with the given name, which grants no commands.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            commands: Vec::new(),
+            urls: Vec::new(),
+            scopes: HashMap::new(),
+        }
+    }
+
+    /// Grants the commands whose names match the pattern.
+    pub fn with_command(mut self, pattern: impl Into<String>) -> Self {
+        self.commands.push(pattern.into());
+        self
+    }
+
+    /// Restricts the capability to the pages whose URLs match the pattern, e.g.
+    /// `http://tauriless-assets.localhost/viewer/*`. Without any patterns, the capability is granted
+    /// to all the pages of the WebView.
+    ///
+    /// The URL of the page is taken from the `Referer` header of the request or, failing that, from
+    /// its `Origin` header.
+    pub fn with_url(mut self, pattern: impl Into<String>) -> Self {
+        self.urls.push(pattern.into());
+        self
+    }
+
+    /// Restricts the values of the argument of the command. The argument is looked up by name, see
+    /// `rename_all` of [`#[command]`](crate::command), or, for the arguments of the closures supplied as an array,
+    /// by position, e.g. `"0"`.
+    pub fn with_scope(
+        mut self,
+        command: impl Into<String>,
+        arg: impl Into<String>,
+        scope: Scope,
+    ) -> Self {
+        self.scopes
+            .entry(command.into())
+            .or_default()
+            .insert(arg.into(), scope);
+        self
+    }
+
+    /// Returns the name of the capability.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn is_granted_to(&self, url: Option<&str>) -> bool {
+        if self.urls.is_empty() {
+            return true;
+        }
+        url.is_some_and(|url| self.urls.iter().any(|pattern| glob_match(pattern, url)))
+    }
+
+    fn grants(&self, command: &str) -> bool {
+        self.commands
+            .iter()
+            .any(|pattern| glob_match(&pattern.replace('_', "-"), command))
+    }
+
+    fn scopes_of(&self, command: &str) -> Option<&HashMap<String, Scope>> {
+        self.scopes
+            .iter()
+            .find(|(name, _)| name.replace('_', "-") == command)
+            .map(|(_, scopes)| scopes)
+    }
+}
+
+/// The values allowed for an argument of a command by a [`Capability`].
+///
+/// The value is allowed if it matches any of the allowed patterns, or if there are none, and none of
+/// the denied patterns. The strings are matched as is, and the other values as JSON, e.g. `42` or `true`.
+/// An array is allowed if all of its elements are. An omitted argument is matched as `null`.
+///
+/// Since `*` matches `/` as well, the strings are treated as paths so that they can't escape the patterns:
+///
+/// * the strings with a `..` segment, e.g. `docs/../secret`, are never allowed;
+/// * the denied patterns are also matched against the string with `\` replaced with `/` and with the empty
+///   and the `.` segments removed, so that `docs//private/key` or `docs/./private/key` are denied by `docs/private/*`.
+#[derive(Clone, Debug, Default, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Scope {
+    #[serde(default)]
+    allow: Vec<String>,
+    #[serde(default)]
+    deny: Vec<String>,
+}
+
+impl Scope {
+    /// Creates the [`Scope`] allowing any value.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Allows the values matching the pattern, e.g. `docs/*`.
+    pub fn with_allowed(mut self, pattern: impl Into<String>) -> Self {
+        self.allow.push(pattern.into());
+        self
+    }
+
+    /// Denies the values matching the pattern, even if they are allowed.
+    pub fn with_denied(mut self, pattern: impl Into<String>) -> Self {
+        self.deny.push(pattern.into());
+        self
+    }
+
+    fn allows(&self, value: &serde_json::Value) -> bool {
+        if let serde_json::Value::Array(values) = value {
+            return values.iter().all(|value| self.allows(value));
+        }
+        let text: String = match value {
+            serde_json::Value::String(s) => s.clone(),
+            value => value.to_string(),
+        };
+        if has_parent_segment(&text) {
+            return false;
+        }
+        let allowed: bool =
+            self.allow.is_empty() || self.allow.iter().any(|pattern| glob_match(pattern, &text));
+        let normalized: String = normalize_path(&text);
+        let denied: bool = self.deny.iter().any(|pattern| {
+            glob_match(pattern, &text) || glob_match(&normalize_path(pattern), &normalized)
+        });
+        allowed && !denied
+    }
+}
+
+fn path_segments(path: &str) -> impl Iterator<Item = &str> {
+    path.split(['/', '\\'])
+}
+
+// Whether the path refers to a parent directory, e.g. `docs/../secret` or `..\secret`.
+fn has_parent_segment(path: &str) -> bool {
+    path_segments(path).any(|segment| segment == "..")
+}
+
+// Replaces `\` with `/` and removes the empty and the `.` segments, e.g. `docs/./private//key` becomes
+// `docs/private/key`. The leading `/` of an absolute path is kept.
+fn normalize_path(path: &str) -> String {
+    let segments: Vec<&str> = path_segments(path)
+        .filter(|segment| !segment.is_empty() && *segment != ".")
+        .collect();
+    let normalized: String = segments.join("/");
+    if path.starts_with(['/', '\\']) {
+        format!("/{normalized}")
+    } else {
+        normalized
+    }
+}
+
+/// The set of the [`Capability`]s granted to a WebView, passed to [`Config::with_capabilities`](crate::Config::with_capabilities).
+///
+/// Once the capabilities are set, only the commands granted by them can be invoked. The other requests
+/// are rejected with `403 Forbidden` before the commands are called. The WebViews with different
+/// [`Config`](crate::Config)s have their own capabilities.
+///
+/// The capabilities can be declared in Rust or loaded from a JSON file or, with the `toml` feature, a TOML file:
+///
+/// ```toml
+/// [[capabilities]]
+/// name = "viewer"
+/// urls = ["http://tauriless-assets.localhost/viewer/*"]
+/// commands = ["get_document", "plugin:fs|read"]
+///
+/// [capabilities.scopes."plugin:fs|read"]
+/// path = { allow = ["docs/*"], deny = ["docs/private/*"] }
+/// ```
+#[derive(Clone, Debug, Default, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Capabilities {
+    capabilities: Vec<Capability>,
+}
+
+/// The error of loading the [`Capabilities`].
+#[derive(thiserror::Error, Debug)]
+pub enum CapabilitiesError {
+    /// The file couldn't be read.
+    #[error("failed to read the capabilities: {0}")]
+    Io(#[from] std::io::Error),
+    /// The JSON is invalid.
+    #[error("failed to parse the capabilities as JSON: {0}")]
+    Json(#[from] serde_json::Error),
+    /// The TOML is invalid.
+    #[cfg(feature = "toml")]
+    #[error("failed to parse the capabilities as TOML: {0}")]
+    Toml(#[from] toml::de::Error),
+    /// The extension of the file is neither `json` nor, with the `toml` feature, `toml`.
+    #[error("unsupported format of the capabilities file: {0}")]
+    UnsupportedFormat(PathBuf),
+}
+
+impl Capabilities {
+    /// Creates the empty [`Capabilities`], which grant no commands.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the [`Capability`].
+    pub fn with(mut self, capability: Capability) -> Self {
+        self.capabilities.push(capability);
+        self
+    }
+
+    /// Parses the capabilities from JSON.
+    pub fn from_json_str(json: &str) -> Result<Self, CapabilitiesError> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    /// Parses the capabilities from TOML.
+    #[cfg(feature = "toml")]
+    pub fn from_toml_str(toml: &str) -> Result<Self, CapabilitiesError> {
+        Ok(toml::from_str(toml)?)
+    }
+
+    /// Loads the capabilities from the file, whose format is determined by its extension.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, CapabilitiesError> {
+        let path: &Path = path.as_ref();
+        let contents: String = std::fs::read_to_string(path)?;
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("json") => Self::from_json_str(&contents),
+            #[cfg(feature = "toml")]
+            Some("toml") => Self::from_toml_str(&contents),
+            _ => Err(CapabilitiesError::UnsupportedFormat(path.to_path_buf())),
+        }
+    }
+
+    /// Checks whether the request with the given headers and body may invoke the command with the given
+    /// URL name. Returns the name of the capability granting it.
+    pub(crate) fn check(
+        &self,
+        command: &str,
+        arg_names: &[&str],
+        headers: &HeaderMap,
+        body: &[u8],
+    ) -> Option<&str> {
+        let url: Option<&str> = headers
+            .get(header::REFERER)
+            .or_else(|| headers.get(header::ORIGIN))
+            .and_then(|url| url.to_str().ok());
+        // The arguments are decoded only if any scope applies.
+        let mut args: Option<Option<serde_json::Value>> = None;
+        self.capabilities
+            .iter()
+            .filter(|capability| capability.is_granted_to(url) && capability.grants(command))
+            .find(|capability| {
+                let Some(scopes) = capability.scopes_of(command) else {
+                    return true;
+                };
+                let args: &Option<serde_json::Value> =
+                    args.get_or_insert_with(|| tauriless_serde::slice_to_deserialize(body).ok());
+                // The arguments that can't be decoded are not allowed by any scope.
+                let Some(args) = args else {
+                    return false;
+                };
+                scopes.iter().all(|(arg, scope)| {
+                    let value: &serde_json::Value =
+                        arg_value(args, arg_names, arg).unwrap_or(&serde_json::Value::Null);
+                    scope.allows(value)
+                })
+            })
+            .map(Capability::name)
+    }
+}
+
+// Looks up the argument supplied either by name or by position.
+fn arg_value<'a>(
+    args: &'a serde_json::Value,
+    arg_names: &[&str],
+    arg: &str,
+) -> Option<&'a serde_json::Value> {
+    match args {
+        serde_json::Value::Object(args) => args.get(arg),
+        serde_json::Value::Array(args) => {
+            let position: usize = match arg_names.iter().position(|name| *name == arg) {
+                Some(position) => position,
+                None => arg.parse().ok()?,
+            };
+            args.get(position)
+        }
+        _ => None,
+    }
+}
+
+// Matches the text against the pattern, where `*` matches any sequence of characters.
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let mut parts = pattern.split('*');
+    // There's always the first part, which must be a prefix.
+    let first: &str = parts.next().unwrap_or("");
+    let Some(mut rest) = text.strip_prefix(first) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        // There's no `*`.
+        return rest.is_empty();
+    };
+    for part in middle {
+        match rest.find(part) {
+            Some(start) => rest = &rest[start + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
@@ -1,12 +1,56 @@
+use tauriless_common::auth::AUTH_TOKENS_GLOBAL;
+use tauriless_common::url::{native_base_url, BASE_URLS_GLOBAL};
+
+use crate::{CommandRegistry, Config, Router};
+
 pub trait Commands {
-    fn register_commands(self, builder: wry::WebViewBuilder) -> wry::WebViewBuilder;
+    fn register_commands(self, builder: wry::WebViewBuilder, config: Config)
+        -> wry::WebViewBuilder;
 }
 
 impl<F> Commands for F
 where
-    F: Fn(wry::WebViewBuilder) -> wry::WebViewBuilder,
+    F: Fn(wry::WebViewBuilder, Config) -> wry::WebViewBuilder,
 {
-    fn register_commands(self, builder: wry::WebViewBuilder) -> wry::WebViewBuilder {
-        self(builder)
+    fn register_commands(
+        self,
+        builder: wry::WebViewBuilder,
+        config: Config,
+    ) -> wry::WebViewBuilder {
+        self(builder, config)
+    }
+}
+
+// The requests are dispatched by the `Router`, so the integration with `wry` is merely an adapter.
+impl Commands for CommandRegistry {
+    fn register_commands(
+        self,
+        builder: wry::WebViewBuilder,
+        config: Config,
+    ) -> wry::WebViewBuilder {
+        let protocol: String = config.protocol().to_string();
+        // `tauriless-js` can't know the platform at compile time, so the base URL is defined for it.
+        // The WebView may serve several protocols, hence the map.
+        let key = serde_json::Value::String(protocol.clone());
+        let mut script: String = format!(
+            "(window.{BASE_URLS_GLOBAL} ??= {{}})[{key}] = {};",
+            serde_json::Value::String(native_base_url(&protocol))
+        );
+        if let Some(token) = config.security().auth_token() {
+            script += &format!(
+                "(window.{AUTH_TOKENS_GLOBAL} ??= {{}})[{key}] = {};",
+                serde_json::Value::String(token.to_string())
+            );
+        }
+        let router = Router::new(self, config);
+        builder
+            .with_initialization_script(&script)
+            .with_asynchronous_custom_protocol(
+                protocol,
+                move |request: wry::http::Request<Vec<u8>>,
+                      responder: wry::RequestAsyncResponder| {
+                    router.dispatch(request, move |response| responder.respond(response))
+                },
+            )
     }
 }
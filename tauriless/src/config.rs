@@ -0,0 +1,183 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use tauriless_common::url::TAURILESS_PROTOCOL;
+
+use crate::cancellation::{invocation_id, Invocation, Invocations};
+use crate::spawner::DefaultSpawner;
+use crate::{Capabilities, Command, Limits, Security, Sleep, Spawner, StateManager};
+
+/// The configuration of the `tauriless` commands, passed to
+/// [`with_tauriless_commands_and_config`](crate::WebViewBuilderExt::with_tauriless_commands_and_config).
+///
+/// ## Example
+///
+/// ```rust,no_run
+/// use tauriless::{Config, StateManager, TokioSpawner};
+///
+/// struct Multiplier(i32);
+///
+/// let rt = tokio::runtime::Builder::new_current_thread().build().unwrap();
+/// let config = Config::new()
+///     .with_state(StateManager::new().with(Multiplier(2)))
+///     .with_spawner(TokioSpawner::new(rt.handle().clone()));
+/// ```
+pub struct Config {
+    state: StateManager,
+    spawner: Arc<dyn Spawner>,
+    invocations: Invocations,
+    default_timeout: Option<Duration>,
+    protocol: String,
+    security: Security,
+    capabilities: Option<Arc<Capabilities>>,
+    limits: Limits,
+}
+
+impl Config {
+    /// Creates the default [`Config`] with no managed state.
+    ///
+    /// Unless [`Config::with_spawner`] is used, the asynchronous and the blocking commands are spawned
+    /// on the tokio runtime whose context is entered on the thread of the event loop.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the state available to the commands taking [`State`](crate::State) arguments.
+    pub fn with_state(mut self, state: StateManager) -> Self {
+        self.state = state;
+        self
+    }
+
+    /// Sets the [`Spawner`] running the asynchronous and the blocking commands.
+    pub fn with_spawner<S: Spawner>(mut self, spawner: S) -> Self {
+        self.spawner = Arc::new(spawner);
+        self
+    }
+
+    /// Sets the timeout for the asynchronous commands without `#[command(timeout = "...")]`.
+    ///
+    /// The commands that don't complete in time are dropped, and the promises returned by `invoke`
+    /// from `tauriless-js` are rejected with a `TimeoutError`. By default, there's no timeout.
+    pub fn with_default_timeout(mut self, timeout: Duration) -> Self {
+        self.default_timeout = Some(timeout);
+        self
+    }
+
+    /// Sets the name of the custom protocol, i.e. the URL scheme, serving the commands. By default, it's
+    /// [`TAURILESS_PROTOCOL`](tauriless_common::url::TAURILESS_PROTOCOL).
+    ///
+    /// The WebViews with different command sets, or the libraries registering their own commands, use different
+    /// protocols so that they don't collide. The frontend invokes the commands with the client created by
+    /// `createClient({ scheme })` from `tauriless-js`. The name must be a valid URL scheme, e.g. `my-app`.
+    pub fn with_protocol(mut self, protocol: impl Into<String>) -> Self {
+        self.protocol = protocol.into();
+        self
+    }
+
+    /// Sets the [`Security`] configuration, e.g. the origins allowed to invoke the commands.
+    pub fn with_security(mut self, security: Security) -> Self {
+        self.security = security;
+        self
+    }
+
+    pub(crate) fn security(&self) -> &Security {
+        &self.security
+    }
+
+    /// Restricts the commands that can be invoked to the ones granted by the [`Capabilities`].
+    /// By default, all the commands can be invoked.
+    pub fn with_capabilities(mut self, capabilities: Capabilities) -> Self {
+        self.capabilities = Some(Arc::new(capabilities));
+        self
+    }
+
+    pub(crate) fn capabilities(&self) -> Option<&Capabilities> {
+        self.capabilities.as_deref()
+    }
+
+    /// Sets the [`Limits`] of the arguments of the commands. The requests whose bodies are too large are rejected
+    /// with `413 Payload Too Large`, and the ones whose arguments are nested too deep or contain too long
+    /// collections with `400 Bad Request`, before the arguments are deserialized.
+    ///
+    /// By default, the [`Limits::default`] apply, which `tauriless-js` checks before sending the arguments too.
+    pub fn with_limits(mut self, limits: Limits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    pub(crate) fn limits(&self) -> &Limits {
+        &self.limits
+    }
+
+    /// Returns the name of the custom protocol serving the commands.
+    pub(crate) fn protocol(&self) -> &str {
+        &self.protocol
+    }
+
+    #[doc(hidden)]
+    pub fn state(&self) -> &StateManager {
+        &self.state
+    }
+
+    #[doc(hidden)]
+    pub fn spawner(&self) -> &dyn Spawner {
+        &*self.spawner
+    }
+
+    /// Returns the timeout of the command, if any.
+    #[doc(hidden)]
+    pub fn timeout<C: Command>(&self) -> Option<Duration> {
+        C::TIMEOUT.or(self.default_timeout)
+    }
+
+    /// Starts the sleep for the timeout of the command, if any.
+    #[doc(hidden)]
+    pub fn sleep_for_timeout<C: Command>(&self) -> Option<Sleep> {
+        self.timeout::<C>()
+            .map(|timeout| self.spawner.sleep(timeout))
+    }
+
+    /// Returns the [`Config`] for the commands of a [`Plugin`](crate::Plugin), which have their own state.
+    pub(crate) fn for_plugin(&self, state: StateManager) -> Config {
+        Config {
+            state,
+            spawner: self.spawner.clone(),
+            invocations: self.invocations.clone(),
+            default_timeout: self.default_timeout,
+            protocol: self.protocol.clone(),
+            security: self.security.clone(),
+            capabilities: self.capabilities.clone(),
+            limits: self.limits,
+        }
+    }
+
+    /// Registers the invocation with the id from the URL, if any, so that it can be cancelled.
+    #[doc(hidden)]
+    pub fn register_invocation(&self, uri: &wry::http::Uri) -> Invocation {
+        self.invocations.register(invocation_id(uri))
+    }
+
+    /// Cancels the invocation with the id from the URL. Returns `false` if there's no such invocation in progress.
+    #[doc(hidden)]
+    pub fn cancel_invocation(&self, uri: &wry::http::Uri) -> bool {
+        match invocation_id(uri) {
+            Some(id) => self.invocations.cancel(id),
+            None => false,
+        }
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            state: StateManager::new(),
+            spawner: Arc::new(DefaultSpawner::default()),
+            invocations: Invocations::default(),
+            default_timeout: None,
+            protocol: TAURILESS_PROTOCOL.to_string(),
+            security: Security::default(),
+            capabilities: None,
+            limits: Limits::default(),
+        }
+    }
+}
@@ -0,0 +1,214 @@
+//! The HTTP server exposing the commands to the frontend running in a regular browser during development,
+//! e.g. with the hot module replacement of the Vite dev server.
+//!
+//! The server dispatches the requests with the same [`Router`] as the WebView, so the commands are invoked
+//! at `http://127.0.0.1:<port>/<command>`. The frontend targets the server with `setBaseUrl` from `tauriless-js`.
+//!
+//! Available with the `dev-server` feature.
+//!
+//! ## Example
+//!
+//! ```rust,no_run
+//! use tauriless::dev_server::DevServer;
+//! use tauriless::{command, commands, Config, TokioSpawner};
+//!
+//! #[command]
+//! fn add(a: i32, b: i32) -> i32 {
+//!     a + b
+//! }
+//!
+//! fn main() -> std::io::Result<()> {
+//!     let rt = tokio::runtime::Builder::new_current_thread().enable_all().build()?;
+//!     let config = Config::new().with_spawner(TokioSpawner::new(rt.handle().clone()));
+//!     let server = DevServer::new(commands!(add), config)
+//!         .with_port(3001)
+//!         .with_allowed_origin("http://localhost:5173");
+//!     rt.block_on(server.serve())
+//! }
+//! ```
+//!
+//! The frontend then calls `setBaseUrl("http://127.0.0.1:3001")` before invoking the commands.
+
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+use axum::body::Body;
+use axum::http::{HeaderName, HeaderValue, Method};
+use axum::response::IntoResponse;
+use http_body_util::LengthLimitError;
+
+use tauriless_common::auth::AUTH_TOKEN_HEADER;
+
+use crate::{CommandRegistry, Config, Router};
+
+/// The origin of the Vite dev server, which is allowed unless [`DevServer::with_allowed_origin`] is used.
+pub const VITE_DEV_ORIGIN: &str = "http://localhost:5173";
+
+/// The server exposing the commands over HTTP during development. See the [module-level docs](self).
+#[derive(Clone, Debug)]
+pub struct DevServer {
+    router: Router,
+    addr: SocketAddr,
+    allowed_origins: Vec<String>,
+}
+
+impl DevServer {
+    /// Creates the [`DevServer`] dispatching the requests to the given commands, setting up their plugins.
+    ///
+    /// By default, the server listens on `127.0.0.1` on a port chosen by the OS.
+    pub fn new(commands: CommandRegistry, config: Config) -> Self {
+        Self::from_router(Router::new(commands, config))
+    }
+
+    /// Creates the [`DevServer`] dispatching the requests with the given [`Router`].
+    pub fn from_router(router: Router) -> Self {
+        Self {
+            router,
+            addr: SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 0),
+            allowed_origins: Vec::new(),
+        }
+    }
+
+    /// Sets the port the server listens on. With `0`, the port is chosen by the OS.
+    pub fn with_port(mut self, port: u16) -> Self {
+        self.addr.set_port(port);
+        self
+    }
+
+    /// Sets the address the server listens on.
+    pub fn with_addr(mut self, addr: SocketAddr) -> Self {
+        self.addr = addr;
+        self
+    }
+
+    /// Allows the cross-origin requests from the given origin, e.g. `http://localhost:5173`.
+    ///
+    /// Unless any origin is allowed explicitly, only [`VITE_DEV_ORIGIN`] is. The requests carrying the `Origin`
+    /// header of other origins are rejected with `403 Forbidden` without invoking the commands.
+    pub fn with_allowed_origin(mut self, origin: impl Into<String>) -> Self {
+        self.allowed_origins.push(origin.into());
+        self
+    }
+
+    /// Returns the [`axum::Router`] serving the commands, e.g. to be nested into the router of the application.
+    pub fn into_axum_router(self) -> axum::Router {
+        let allowed_origins: Vec<String> = if self.allowed_origins.is_empty() {
+            vec![VITE_DEV_ORIGIN.to_string()]
+        } else {
+            self.allowed_origins
+        };
+        let router: Router = self.router;
+        axum::Router::new().fallback(move |request: axum::extract::Request| {
+            let router: Router = router.clone();
+            let allowed_origins: Vec<String> = allowed_origins.clone();
+            async move { handle(&router, &allowed_origins, request).await }
+        })
+    }
+
+    /// Binds the listener and serves the commands until the server fails.
+    ///
+    /// When the port is chosen by the OS, [`DevServer::serve_on`] allows to learn it.
+    pub async fn serve(self) -> std::io::Result<()> {
+        let listener = tokio::net::TcpListener::bind(self.addr).await?;
+        self.serve_on(listener).await
+    }
+
+    /// Serves the commands on the given listener until the server fails, ignoring the configured address.
+    ///
+    /// Binding the listener beforehand allows to learn the port chosen by the OS, e.g. to pass it to the frontend.
+    pub async fn serve_on(self, listener: tokio::net::TcpListener) -> std::io::Result<()> {
+        axum::serve(listener, self.into_axum_router()).await
+    }
+}
+
+async fn handle(
+    router: &Router,
+    allowed_origins: &[String],
+    request: axum::extract::Request,
+) -> axum::response::Response {
+    // The requests from the other origins are rejected before they are dispatched, since the "simple"
+    // requests, e.g. with `text/plain` bodies, aren't preflighted, and CORS would only hide their responses
+    // but not prevent their side effects. The requests without the `Origin` header, e.g. the ones sent
+    // by `curl`, aren't sent by the pages of other origins.
+    let origin: Option<HeaderValue> = request.headers().get(axum::http::header::ORIGIN).cloned();
+    let is_allowed: bool = origin.as_ref().is_none_or(|origin| {
+        allowed_origins
+            .iter()
+            .any(|allowed| origin.as_bytes() == allowed.as_bytes())
+    });
+    let mut response: axum::response::Response = if !is_allowed {
+        (
+            axum::http::StatusCode::FORBIDDEN,
+            "Forbidden: the origin is not allowed to invoke the commands.",
+        )
+            .into_response()
+    } else if request.method() == Method::OPTIONS {
+        // The preflight request.
+        let mut response = axum::http::StatusCode::NO_CONTENT.into_response();
+        response.headers_mut().insert(
+            axum::http::header::ACCESS_CONTROL_ALLOW_METHODS,
+            HeaderValue::from_static("POST"),
+        );
+        response.headers_mut().insert(
+            axum::http::header::ACCESS_CONTROL_ALLOW_HEADERS,
+            HeaderValue::from_str(&format!("content-type, {AUTH_TOKEN_HEADER}")).unwrap(),
+        );
+        response
+    } else {
+        dispatch(router, request).await
+    };
+    // The responses of the `Router` allow any origin, which is only acceptable for the WebView.
+    let headers = response.headers_mut();
+    headers.remove(axum::http::header::ACCESS_CONTROL_ALLOW_ORIGIN);
+    if let Some(origin) = origin.filter(|_| is_allowed) {
+        headers.insert(axum::http::header::ACCESS_CONTROL_ALLOW_ORIGIN, origin);
+    }
+    headers.insert(axum::http::header::VARY, HeaderValue::from_static("origin"));
+    response
+}
+
+// The requests and the responses are converted field by field, so the versions of `http` used by `axum`
+// and `wry` don't have to match.
+async fn dispatch(router: &Router, request: axum::extract::Request) -> axum::response::Response {
+    let (parts, body) = request.into_parts();
+    // The body is read up to the limit of the `Router`, which would reject the larger one anyway.
+    let max_body_size: usize = router.limits().max_body_size;
+    let body: Vec<u8> = match axum::body::to_bytes(body, max_body_size).await {
+        Ok(body) => body.to_vec(),
+        Err(e) if std::error::Error::source(&e).is_some_and(|e| e.is::<LengthLimitError>()) => {
+            let message = format!(
+                "Payload too large: the body of the request exceeds the limit of {max_body_size} bytes."
+            );
+            return (axum::http::StatusCode::PAYLOAD_TOO_LARGE, message).into_response();
+        }
+        Err(e) => {
+            return (axum::http::StatusCode::BAD_REQUEST, e.to_string()).into_response();
+        }
+    };
+    let path_and_query: &str = parts
+        .uri
+        .path_and_query()
+        .map(|path_and_query| path_and_query.as_str())
+        .unwrap_or("/");
+    let mut request = crate::http::Request::builder()
+        .method(parts.method.as_str())
+        .uri(path_and_query);
+    // The headers are forwarded for the `Security` of the `Config`, e.g. the `Origin` header.
+    for (name, value) in &parts.headers {
+        request = request.header(name.as_str(), value.as_bytes());
+    }
+    let request = match request.body(body) {
+        Ok(request) => request,
+        Err(e) => {
+            return (axum::http::StatusCode::BAD_REQUEST, e.to_string()).into_response();
+        }
+    };
+    let response: crate::http::Response<Vec<u8>> = router.handle(request).await;
+
+    let mut builder = axum::http::Response::builder().status(response.status().as_u16());
+    for (name, value) in response.headers() {
+        let name = HeaderName::from_bytes(name.as_str().as_bytes()).unwrap();
+        let value = HeaderValue::from_bytes(value.as_bytes()).unwrap();
+        builder = builder.header(name, value);
+    }
+    builder.body(Body::from(response.into_body())).unwrap()
+}
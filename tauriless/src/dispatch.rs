@@ -0,0 +1,179 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use crate::handlers::handle_local_executor_stopped;
+use crate::panic::PanicPayload;
+use crate::router::SharedResponder;
+use crate::{
+    cancellable, catch_unwind, deserialize_args, handle_command_cancelled, handle_command_panic,
+    handle_command_return, handle_command_timeout, handle_deserialization_error, timeout,
+    CancellationToken, CatchUnwind, Command, Config, Invocation, Responder, Sleep,
+};
+
+// These functions implement `Command::handle` for the different kinds of commands. Each of them
+// has the bounds that only its kind of commands satisfies, so `#[command]` picks the one to call.
+
+// Responds to the request with either the return value of the command or the panic it caused.
+// Otherwise, the responder would be dropped and the promise on the frontend would never settle.
+fn respond<C: Command>(responder: Responder, ret: Result<C::RetTy, PanicPayload>) {
+    // The `Serialize` impl of the return value may panic as well.
+    match ret.and_then(|ret| catch_unwind(|| handle_command_return::<C>(ret))) {
+        Ok(response) => responder.respond(response),
+        Err(payload) => responder.respond(handle_command_panic(C::NAME, payload)),
+    }
+}
+
+// Unless the command observes the cancellation itself, its future is dropped on cancellation.
+// The future is dropped on timeout regardless.
+fn respond_async<C: Command>(
+    config: &Config,
+    responder: Responder,
+    ret: Option<Option<Result<C::RetTy, PanicPayload>>>,
+) {
+    match ret {
+        Some(Some(ret)) => respond::<C>(responder, ret),
+        Some(None) => responder.respond(handle_command_cancelled(C::NAME)),
+        None => {
+            let timeout: std::time::Duration = config.timeout::<C>().unwrap();
+            responder.respond(handle_command_timeout(C::NAME, timeout))
+        }
+    }
+}
+
+/// Runs the synchronous command on the thread of the event loop.
+#[doc(hidden)]
+pub fn dispatch_sync<C: Command>(
+    config: &Arc<Config>,
+    _uri: &wry::http::Uri,
+    body: Vec<u8>,
+    responder: Responder,
+) {
+    let args: C::Args = match deserialize_args::<C>(body.as_slice()) {
+        Ok(args) => args,
+        Err(e) => return responder.respond(handle_deserialization_error(C::NAME, e)),
+    };
+    // The command blocks the event loop, so it can't be cancelled while it's running.
+    let token = CancellationToken::new();
+    // The panic must not unwind through the event loop.
+    let ret: Result<C::RetTy, PanicPayload> =
+        catch_unwind(|| C::sync_command(args, config.state(), &token));
+    respond::<C>(responder, ret);
+}
+
+/// Runs the `#[command(blocking)]` command on the blocking thread pool of the [`Spawner`](crate::Spawner).
+#[doc(hidden)]
+pub fn dispatch_blocking<C>(
+    config: &Arc<Config>,
+    uri: &wry::http::Uri,
+    body: Vec<u8>,
+    responder: Responder,
+) where
+    C: Command + 'static,
+    C::Args: Send,
+{
+    let args: C::Args = match deserialize_args::<C>(body.as_slice()) {
+        Ok(args) => args,
+        Err(e) => return responder.respond(handle_deserialization_error(C::NAME, e)),
+    };
+    let config: Arc<Config> = config.clone();
+    let invocation: Invocation = config.register_invocation(uri);
+    // The command is run on the blocking thread pool so that it doesn't block the event loop.
+    config.clone().spawner().spawn_blocking(Box::new(move || {
+        let ret: Result<C::RetTy, PanicPayload> =
+            catch_unwind(|| C::sync_command(args, config.state(), invocation.token()));
+        respond::<C>(responder, ret);
+    }));
+}
+
+/// Spawns the future of the asynchronous command with the [`Spawner`](crate::Spawner).
+#[doc(hidden)]
+pub fn dispatch_async<C>(
+    config: &Arc<Config>,
+    uri: &wry::http::Uri,
+    body: Vec<u8>,
+    responder: Responder,
+) where
+    C: Command + 'static,
+    C::Args: Send,
+{
+    let args: C::Args = match deserialize_args::<C>(body.as_slice()) {
+        Ok(args) => args,
+        Err(e) => return responder.respond(handle_deserialization_error(C::NAME, e)),
+    };
+    let config: Arc<Config> = config.clone();
+    let invocation: Invocation = config.register_invocation(uri);
+    let sleep: Option<Sleep> = config.sleep_for_timeout::<C>();
+    config.clone().spawner().spawn(Box::pin(async move {
+        let token: &CancellationToken = invocation.token();
+        // The future is created inside of the `CatchUnwind` so that the panics while extracting
+        // the `State` arguments are caught as well.
+        let ret = timeout(
+            sleep,
+            cancellable::<C, _>(
+                token,
+                CatchUnwind::new(async { C::async_command(args, config.state(), token).await }),
+            ),
+        )
+        .await;
+        respond_async::<C>(&config, responder, ret);
+    }));
+}
+
+/// Spawns the future of the `#[command(local)]` command on the single-threaded executor of the
+/// [`Spawner`](crate::Spawner).
+#[doc(hidden)]
+pub fn dispatch_local<C>(
+    config: &Arc<Config>,
+    uri: &wry::http::Uri,
+    body: Vec<u8>,
+    responder: Responder,
+) where
+    C: Command + 'static,
+{
+    let config: Arc<Config> = config.clone();
+    let invocation: Invocation = config.register_invocation(uri);
+    // The timeout includes the time spent in the queue of the local executor.
+    let sleep: Option<Sleep> = config.sleep_for_timeout::<C>();
+    // The request is answered from here if the task panics outside of the command or is never run.
+    let shared = SharedResponder::new(responder);
+    let task_shared: SharedResponder = shared.clone();
+    // Both the arguments and the future are created on the thread of the local executor,
+    // so neither of them has to be `Send`.
+    let spawned = config.clone().spawner().spawn_local(Box::new(
+        move || -> Pin<Box<dyn Future<Output = ()>>> {
+            Box::pin(async move {
+                let responder: Responder = task_shared.responder();
+                // The panics while deserializing the arguments are caught as well, so that the request
+                // is answered before the local executor drops the task.
+                let ret = CatchUnwind::new(async move {
+                    let args: C::Args = match deserialize_args::<C>(body.as_slice()) {
+                        Ok(args) => args,
+                        Err(e) => {
+                            return responder.respond(handle_deserialization_error(C::NAME, e))
+                        }
+                    };
+                    let token: &CancellationToken = invocation.token();
+                    let ret = timeout(
+                        sleep,
+                        cancellable::<C, _>(
+                            token,
+                            CatchUnwind::new(async {
+                                C::local_async_command(args, config.state(), token).await
+                            }),
+                        ),
+                    )
+                    .await;
+                    respond_async::<C>(&config, responder, ret);
+                })
+                .await;
+                if let Err(payload) = ret {
+                    task_shared.respond(handle_command_panic(C::NAME, payload));
+                }
+            })
+        },
+    ));
+    if spawned.is_err() {
+        shared.respond(handle_local_executor_stopped(C::NAME));
+    }
+}
@@ -1,18 +1,24 @@
 use std::borrow::Cow;
 
+use crate::panic::{panic_message, PanicPayload};
+use crate::throttle::Throttled;
+use crate::Command;
+
 // These error handlers are used in custom protocol handlers.
 
 #[doc(hidden)]
 pub fn handle_deserialization_error(
     cmd_name: &str,
-    e: tauriless_serde::slice_to_deserialize::Error,
+    e: tauriless_serde::slice_to_deserialize_args::Error,
 ) -> wry::http::response::Response<Cow<'static, [u8]>> {
     #[cfg(debug_assertions)]
     println!("Failed to deserialize to `{cmd_name}::Args`: {e:?}");
     let mut body = Vec::new();
     body.extend_from_slice(b"Bad request: failed to deserialize `");
     body.extend_from_slice(cmd_name.as_bytes());
-    body.extend_from_slice(b"::Args`.");
+    body.extend_from_slice(b"::Args`: ");
+    body.extend_from_slice(e.to_string().as_bytes());
+    body.extend_from_slice(b".");
     wry::http::response::Response::builder()
         .status(wry::http::StatusCode::BAD_REQUEST)
         .header(
@@ -56,3 +62,315 @@ pub fn handle_unknown_command(cmd_name: &str) -> wry::http::response::Response<C
         ))
         .unwrap()
 }
+
+#[doc(hidden)]
+pub fn handle_command_error(
+    cmd_name: &str,
+    serialized_err: Vec<u8>,
+) -> wry::http::response::Response<Cow<'static, [u8]>> {
+    #[cfg(debug_assertions)]
+    println!("`tauriless` command '{cmd_name}' returned an error.");
+    wry::http::response::Response::builder()
+        .status(tauriless_common::status::COMMAND_ERROR)
+        .header(
+            wry::http::header::ACCESS_CONTROL_ALLOW_ORIGIN,
+            wry::http::HeaderValue::from_static("*"),
+        )
+        .body(Cow::<'static, [u8]>::Owned(serialized_err))
+        .unwrap()
+}
+
+// The panic message is only sent in debug builds because it may reveal the internals of the application.
+#[doc(hidden)]
+pub fn handle_command_panic(
+    cmd_name: &str,
+    payload: PanicPayload,
+) -> wry::http::response::Response<Cow<'static, [u8]>> {
+    let message: &str = panic_message(&payload).unwrap_or("Box<dyn Any>");
+    #[cfg(debug_assertions)]
+    println!("`tauriless` command '{cmd_name}' panicked: {message}");
+    let mut body = Vec::new();
+    body.extend_from_slice(b"Internal server error: the `");
+    body.extend_from_slice(cmd_name.as_bytes());
+    body.extend_from_slice(b"` command panicked");
+    if cfg!(debug_assertions) {
+        body.extend_from_slice(b": ");
+        body.extend_from_slice(message.as_bytes());
+    }
+    body.extend_from_slice(b".");
+    wry::http::response::Response::builder()
+        .status(wry::http::StatusCode::INTERNAL_SERVER_ERROR)
+        .header(
+            wry::http::header::ACCESS_CONTROL_ALLOW_ORIGIN,
+            wry::http::HeaderValue::from_static("*"),
+        )
+        .body(Cow::<'static, [u8]>::Owned(body))
+        .unwrap()
+}
+
+#[doc(hidden)]
+pub fn handle_command_cancelled(
+    cmd_name: &str,
+) -> wry::http::response::Response<Cow<'static, [u8]>> {
+    #[cfg(debug_assertions)]
+    println!("`tauriless` command '{cmd_name}' was cancelled.");
+    wry::http::response::Response::builder()
+        .status(tauriless_common::status::COMMAND_CANCELLED)
+        .header(
+            wry::http::header::ACCESS_CONTROL_ALLOW_ORIGIN,
+            wry::http::HeaderValue::from_static("*"),
+        )
+        .body(Cow::<'static, [u8]>::Borrowed(
+            b"The command was cancelled.",
+        ))
+        .unwrap()
+}
+
+#[doc(hidden)]
+pub fn handle_command_timeout(
+    cmd_name: &str,
+    timeout: std::time::Duration,
+) -> wry::http::response::Response<Cow<'static, [u8]>> {
+    #[cfg(debug_assertions)]
+    println!("`tauriless` command '{cmd_name}' timed out after {timeout:?}.");
+    let body: String =
+        format!("Gateway timeout: the `{cmd_name}` command didn't complete in {timeout:?}.");
+    wry::http::response::Response::builder()
+        .status(tauriless_common::status::COMMAND_TIMEOUT)
+        .header(
+            wry::http::header::ACCESS_CONTROL_ALLOW_ORIGIN,
+            wry::http::HeaderValue::from_static("*"),
+        )
+        .body(Cow::<'static, [u8]>::Owned(body.into_bytes()))
+        .unwrap()
+}
+
+// Unlike the functions above, this one is not an error handler. It's used to respond with the
+// return value of the command, which can be either a success or a failure.
+#[doc(hidden)]
+pub fn handle_command_return<C: Command>(
+    ret: C::RetTy,
+) -> wry::http::response::Response<Cow<'static, [u8]>> {
+    match C::into_result(ret) {
+        Ok(ok) => handle_ok(&ok),
+        Err(err) => match tauriless_serde::serialize_to_vec_u8(&err) {
+            Ok(err) => handle_command_error(C::NAME, err),
+            Err(e) => handle_serialization_error(e),
+        },
+    }
+}
+
+// Responds with the value returned by a command that succeeded.
+pub(crate) fn handle_ok<T: serde::Serialize>(
+    ok: &T,
+) -> wry::http::response::Response<Cow<'static, [u8]>> {
+    match tauriless_serde::serialize_to_vec_u8(ok) {
+        Ok(ok) => wry::http::response::Response::builder()
+            .status(wry::http::StatusCode::OK)
+            .header(
+                wry::http::header::ACCESS_CONTROL_ALLOW_ORIGIN,
+                wry::http::HeaderValue::from_static("*"),
+            )
+            .body(Cow::<'static, [u8]>::Owned(ok))
+            .unwrap(),
+        Err(e) => handle_serialization_error(e),
+    }
+}
+
+// Neither is this one. It responds to the request cancelling
+// the invocation of a command, which may have already completed.
+#[doc(hidden)]
+pub fn handle_cancellation(cancelled: bool) -> wry::http::response::Response<Cow<'static, [u8]>> {
+    #[cfg(debug_assertions)]
+    if !cancelled {
+        println!("The cancelled `tauriless` invocation is not in progress.");
+    }
+    #[cfg(not(debug_assertions))]
+    let _ = cancelled;
+    wry::http::response::Response::builder()
+        .status(wry::http::StatusCode::NO_CONTENT)
+        .header(
+            wry::http::header::ACCESS_CONTROL_ALLOW_ORIGIN,
+            wry::http::HeaderValue::from_static("*"),
+        )
+        .body(Cow::<'static, [u8]>::Borrowed(b""))
+        .unwrap()
+}
+
+// Responds to the request from an origin that is not allowed by the `Security` of the `Config`.
+// Unlike the responses to the allowed origins, which echo back only the requesting origin, the rejection
+// allows any origin to read it. It reveals neither the allowed origins nor anything produced by the commands,
+// and without it the browser would turn the rejection into a network error, so the page couldn't tell
+// the forbidden origin apart from the unreachable core process.
+pub(crate) fn handle_forbidden_origin() -> wry::http::response::Response<Cow<'static, [u8]>> {
+    #[cfg(debug_assertions)]
+    println!("The origin of the `tauriless` request is not allowed.");
+    wry::http::response::Response::builder()
+        .status(wry::http::StatusCode::FORBIDDEN)
+        .header(
+            wry::http::header::ACCESS_CONTROL_ALLOW_ORIGIN,
+            wry::http::HeaderValue::from_static("*"),
+        )
+        .body(Cow::<'static, [u8]>::Borrowed(
+            b"Forbidden: the origin is not allowed to invoke the commands.",
+        ))
+        .unwrap()
+}
+
+// Responds to the request whose dispatch panicked outside of the command, e.g. in a middleware or
+// in the `Spawner`, like to the one whose command panicked.
+pub(crate) fn handle_dispatch_panic(
+    cmd_name: &str,
+    payload: PanicPayload,
+) -> wry::http::response::Response<Cow<'static, [u8]>> {
+    let message: &str = panic_message(&payload).unwrap_or("Box<dyn Any>");
+    #[cfg(debug_assertions)]
+    println!("The dispatch of the `tauriless` command '{cmd_name}' panicked: {message}");
+    let mut body: String =
+        format!("Internal server error: the dispatch of the `{cmd_name}` command panicked");
+    if cfg!(debug_assertions) {
+        body.push_str(": ");
+        body.push_str(message);
+    }
+    body.push('.');
+    wry::http::response::Response::builder()
+        .status(wry::http::StatusCode::INTERNAL_SERVER_ERROR)
+        .header(
+            wry::http::header::ACCESS_CONTROL_ALLOW_ORIGIN,
+            wry::http::HeaderValue::from_static("*"),
+        )
+        .body(Cow::<'static, [u8]>::Owned(body.into_bytes()))
+        .unwrap()
+}
+
+// Responds to the request invoking a `#[command(local)]` command when the executor of such commands
+// is no longer running, e.g. after its runtime was shut down.
+pub(crate) fn handle_local_executor_stopped(
+    cmd_name: &str,
+) -> wry::http::response::Response<Cow<'static, [u8]>> {
+    #[cfg(debug_assertions)]
+    println!("The local executor of the `tauriless` command '{cmd_name}' is not running.");
+    let body: String =
+        format!("Internal server error: the executor of the `{cmd_name}` command is not running.");
+    wry::http::response::Response::builder()
+        .status(wry::http::StatusCode::INTERNAL_SERVER_ERROR)
+        .header(
+            wry::http::header::ACCESS_CONTROL_ALLOW_ORIGIN,
+            wry::http::HeaderValue::from_static("*"),
+        )
+        .body(Cow::<'static, [u8]>::Owned(body.into_bytes()))
+        .unwrap()
+}
+
+// Responds to the request without the valid auth token required by the `Security` of the `Config`.
+pub(crate) fn handle_unauthorized() -> wry::http::response::Response<Cow<'static, [u8]>> {
+    #[cfg(debug_assertions)]
+    println!("The `tauriless` request doesn't carry the valid auth token.");
+    wry::http::response::Response::builder()
+        .status(wry::http::StatusCode::UNAUTHORIZED)
+        .header(
+            wry::http::header::ACCESS_CONTROL_ALLOW_ORIGIN,
+            wry::http::HeaderValue::from_static("*"),
+        )
+        .body(Cow::<'static, [u8]>::Borrowed(
+            b"Unauthorized: the request doesn't carry the valid auth token.",
+        ))
+        .unwrap()
+}
+
+// Responds to the CORS preflight request, which precedes the cross-origin requests carrying the auth token.
+pub(crate) fn handle_preflight() -> wry::http::response::Response<Cow<'static, [u8]>> {
+    wry::http::response::Response::builder()
+        .status(wry::http::StatusCode::NO_CONTENT)
+        .header(
+            wry::http::header::ACCESS_CONTROL_ALLOW_ORIGIN,
+            wry::http::HeaderValue::from_static("*"),
+        )
+        .header(
+            wry::http::header::ACCESS_CONTROL_ALLOW_METHODS,
+            wry::http::HeaderValue::from_static("POST"),
+        )
+        .header(
+            wry::http::header::ACCESS_CONTROL_ALLOW_HEADERS,
+            wry::http::HeaderValue::from_static(tauriless_common::auth::AUTH_TOKEN_HEADER),
+        )
+        .body(Cow::<'static, [u8]>::Borrowed(b""))
+        .unwrap()
+}
+
+// Responds to the request invoking a command that is not granted by the `Capabilities` of the `Config`
+// to the page that sent it, or with the arguments out of the scopes.
+pub(crate) fn handle_command_forbidden(
+    cmd_name: &str,
+) -> wry::http::response::Response<Cow<'static, [u8]>> {
+    #[cfg(debug_assertions)]
+    println!("`tauriless` command '{cmd_name}' is not granted by the capabilities.");
+    let body: String =
+        format!("Forbidden: the `{cmd_name}` command is not granted by the capabilities.");
+    wry::http::response::Response::builder()
+        .status(wry::http::StatusCode::FORBIDDEN)
+        .header(
+            wry::http::header::ACCESS_CONTROL_ALLOW_ORIGIN,
+            wry::http::HeaderValue::from_static("*"),
+        )
+        .body(Cow::<'static, [u8]>::Owned(body.into_bytes()))
+        .unwrap()
+}
+
+// Responds to the request whose arguments exceed the `Limits` of the `Config`.
+pub(crate) fn handle_limit_exceeded(
+    cmd_name: &str,
+    e: tauriless_serde::check_limits::Error,
+) -> wry::http::response::Response<Cow<'static, [u8]>> {
+    use tauriless_serde::check_limits::Error;
+
+    #[cfg(debug_assertions)]
+    println!("The arguments of `tauriless` command '{cmd_name}' exceed the limits: {e}");
+    let (status, reason) = match e {
+        Error::BodyTooLarge { .. } => (
+            wry::http::StatusCode::PAYLOAD_TOO_LARGE,
+            "Payload too large",
+        ),
+        _ => (wry::http::StatusCode::BAD_REQUEST, "Bad request"),
+    };
+    let body: String =
+        format!("{reason}: the arguments of the `{cmd_name}` command are rejected: {e}.");
+    wry::http::response::Response::builder()
+        .status(status)
+        .header(
+            wry::http::header::ACCESS_CONTROL_ALLOW_ORIGIN,
+            wry::http::HeaderValue::from_static("*"),
+        )
+        .body(Cow::<'static, [u8]>::Owned(body.into_bytes()))
+        .unwrap()
+}
+
+// Responds to the request invoking a command that is already running the maximum number of invocations
+// or whose rate limit is exhausted.
+pub(crate) fn handle_too_many_requests(
+    cmd_name: &str,
+    throttled: Throttled,
+) -> wry::http::response::Response<Cow<'static, [u8]>> {
+    #[cfg(debug_assertions)]
+    println!("`tauriless` command '{cmd_name}' was throttled: {throttled:?}.");
+    let mut response = wry::http::response::Response::builder()
+        .status(wry::http::StatusCode::TOO_MANY_REQUESTS)
+        .header(
+            wry::http::header::ACCESS_CONTROL_ALLOW_ORIGIN,
+            wry::http::HeaderValue::from_static("*"),
+        );
+    let body: String = match throttled {
+        Throttled::Concurrency(max_concurrency) => format!(
+            "Too many requests: the `{cmd_name}` command is already running {max_concurrency} invocation(s)."
+        ),
+        Throttled::RateLimit { retry_after } => {
+            // `Retry-After` is in whole seconds, so it's rounded up.
+            let seconds: u64 = retry_after.as_secs() + u64::from(retry_after.subsec_nanos() > 0);
+            response = response.header(wry::http::header::RETRY_AFTER, seconds);
+            format!("Too many requests: the rate limit of the `{cmd_name}` command is exceeded, retry in {retry_after:?}.")
+        }
+    };
+    response
+        .body(Cow::<'static, [u8]>::Owned(body.into_bytes()))
+        .unwrap()
+}
@@ -4,30 +4,136 @@ use std::future::Future;
 
 pub use tauriless_macro::{command, commands};
 #[doc(hidden)]
-pub use tauriless_serde::{serialize_to_vec_u8, slice_to_deserialize};
+pub use tauriless_serde::{serialize_to_vec_u8, slice_to_deserialize, Limits};
+/// The re-export of the [`http`](https://docs.rs/http) crate used by [`Router`] and [`wry`].
+///
+/// The code generated by `#[command]` refers to it as well, so the crates defining the commands
+/// don't have to depend on `wry` themselves.
+pub use wry::http;
 
+mod args;
+#[cfg(feature = "assets")]
+pub mod assets;
+mod cancellation;
+mod capabilities;
 mod commands;
+mod config;
+#[cfg(feature = "dev-server")]
+pub mod dev_server;
+mod dispatch;
 mod handlers;
+mod local_executor;
+mod middleware;
+mod panic;
+mod plugin;
+mod registry;
+mod router;
+mod security;
+mod spawner;
+mod state;
+mod throttle;
+#[cfg(feature = "testing")]
+pub mod testing;
+mod timer;
 mod web_view_builder_ext;
 
+#[doc(hidden)]
+pub use args::deserialize_args;
+pub use cancellation::{CancellationToken, Cancelled};
+pub use capabilities::{Capabilities, CapabilitiesError, Capability, Scope};
+#[doc(hidden)]
+pub use cancellation::{cancellable, invocation_id, Invocation};
 #[doc(hidden)]
 pub use commands::Commands;
+pub use config::Config;
+#[doc(hidden)]
+pub use dispatch::{dispatch_async, dispatch_blocking, dispatch_local, dispatch_sync};
 #[doc(hidden)]
 pub use handlers::{
-    handle_deserialization_error, handle_serialization_error, handle_unknown_command,
+    handle_cancellation, handle_command_cancelled, handle_command_error, handle_command_panic,
+    handle_command_return, handle_command_timeout, handle_deserialization_error, handle_serialization_error,
+    handle_unknown_command,
 };
+#[doc(hidden)]
+pub use panic::{catch_unwind, CatchUnwind};
+pub use middleware::{Call, Middleware, Next};
+pub use plugin::Plugin;
+pub use registry::CommandRegistry;
+#[doc(hidden)]
+pub use router::Responder;
+pub use router::Router;
+pub use security::Security;
+#[cfg(feature = "async-std")]
+pub use spawner::AsyncStdSpawner;
+#[cfg(feature = "smol")]
+pub use spawner::SmolSpawner;
+#[cfg(feature = "tokio")]
+pub use spawner::TokioSpawner;
+pub use spawner::{Sleep, SpawnedBlocking, SpawnedFuture, SpawnedLocal, Spawner};
+pub use state::{State, StateManager};
+pub use throttle::RateLimit;
+#[doc(hidden)]
+pub use timer::timeout;
 pub use web_view_builder_ext::WebViewBuilderExt;
 
 #[doc(hidden)]
 pub trait Command {
     const IS_ASYNC: bool;
+    /// Whether the synchronous command is run on the blocking thread pool rather than
+    /// on the thread of the event loop. See `#[command(blocking)]`.
+    const IS_BLOCKING: bool;
+    /// Whether the asynchronous command is run with [`Command::local_async_command`] on the thread of the
+    /// local executor. See `#[command(local)]`.
+    const IS_LOCAL: bool;
+    /// Whether the command takes a [`CancellationToken`] argument. The futures of such commands are not
+    /// dropped on cancellation so that they can clean up cooperatively.
+    const OBSERVES_CANCELLATION: bool;
+    /// The timeout of the asynchronous command set with `#[command(timeout = "...")]`, if any.
+    /// Otherwise, the default timeout from the [`Config`] is used.
+    const TIMEOUT: Option<std::time::Duration>;
+    /// The maximum number of the invocations of the command running at once, set with
+    /// `#[command(max_concurrency = N)]`, if any.
+    const MAX_CONCURRENCY: Option<usize>;
+    /// The rate limit of the invocations of the command, set with `#[command(rate_limit = "...")]`, if any.
+    const RATE_LIMIT: Option<RateLimit>;
     const NAME: &'static str;
     /// URLs are not allowed to contain underscores, so we use dashes instead.
     const URL_NAME: &'static str;
-    type Args: for<'a> serde::Deserialize<'a>;
+    /// The number of the arguments supplied by the frontend.
+    const ARITY: usize;
+    /// The names of the arguments supplied by the frontend, in the order of the parameters, as expected
+    /// when the arguments are supplied by name.
+    const ARG_NAMES: &'static [&'static str];
+    /// The minimal number of the positional arguments supplied by the frontend. The trailing arguments
+    /// of type `Option<T>` or marked with `#[tauriless(default)]` can be omitted.
+    const MIN_ARITY: usize;
+    /// The arguments supplied by the frontend. The [`State`] arguments are not included.
+    ///
+    /// It's always a tuple with [`Command::ARITY`] elements, including `()` and 1-tuples like `(i32,)`.
+    type Args;
+    /// The arguments supplied by the frontend as a sequence.
+    type PositionalArgs: for<'a> serde::Deserialize<'a>;
+    /// The arguments supplied by the frontend as a map keyed by parameter name.
+    type NamedArgs: for<'a> serde::Deserialize<'a>;
     type RetTy: serde::Serialize;
+    /// The type of the value sent to the frontend when the command succeeds.
+    ///
+    /// For commands returning `Result<T, E>`, it's `T`. Otherwise, it's [`Command::RetTy`].
+    type Ok: serde::Serialize;
+    /// The type of the value sent to the frontend when the command fails.
+    ///
+    /// For commands returning `Result<T, E>`, it's `E`. Otherwise, it's [`NoError`].
+    type Err: serde::Serialize;
 
-    fn sync_command(args: Self::Args) -> Self::RetTy;
+    fn from_positional_args(positional_args: Self::PositionalArgs) -> Self::Args;
+    fn from_named_args(named_args: Self::NamedArgs) -> Self::Args;
+    /// Splits the return value of the command into the success and the failure cases.
+    fn into_result(ret: Self::RetTy) -> Result<Self::Ok, Self::Err>;
+    fn sync_command(
+        args: Self::Args,
+        state: &StateManager,
+        cancellation_token: &CancellationToken,
+    ) -> Self::RetTy;
     // Using `async fn` triggers a warning:
     //
     // ```
@@ -35,5 +141,36 @@ pub trait Command {
     // you can suppress this lint if you plan to use the trait only in your own code, or do not care
     // about auto traits like `Send` on the `Future`.
     // ```
-    fn async_command(args: Self::Args) -> impl Future<Output = Self::RetTy> + Send;
+    //
+    // The `State` and `CancellationToken` arguments are extracted eagerly, before the future is created.
+    fn async_command(
+        args: Self::Args,
+        state: &StateManager,
+        cancellation_token: &CancellationToken,
+    ) -> impl Future<Output = Self::RetTy> + Send;
+    /// The counterpart of [`Command::async_command`] for the `#[command(local)]` commands, whose futures
+    /// are not `Send`.
+    fn local_async_command(
+        args: Self::Args,
+        state: &StateManager,
+        cancellation_token: &CancellationToken,
+    ) -> impl Future<Output = Self::RetTy>;
+    /// Handles the request invoking the command by calling the dispatch function for its kind,
+    /// e.g. [`dispatch_async`] for the asynchronous commands. See [`CommandRegistry::register`].
+    fn handle(
+        config: &std::sync::Arc<Config>,
+        uri: &wry::http::Uri,
+        body: Vec<u8>,
+        responder: Responder,
+    );
+}
+
+/// The [`Command::Err`] type for the commands that can't fail.
+#[doc(hidden)]
+pub enum NoError {}
+
+impl serde::Serialize for NoError {
+    fn serialize<S: serde::Serializer>(&self, _serializer: S) -> Result<S::Ok, S::Error> {
+        match *self {}
+    }
 }
@@ -0,0 +1,93 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{mpsc, Arc, OnceLock};
+use std::task::{Context, Poll, Wake, Waker};
+
+use crate::{catch_unwind, SpawnedLocal};
+
+enum Message {
+    Spawn(SpawnedLocal),
+    Wake(usize),
+}
+
+/// The single-threaded executor driving the futures of the `#[command(local)]` commands, which are not `Send`.
+///
+/// The futures are created and polled on the dedicated thread, so neither the futures nor the arguments
+/// of the commands have to be `Send`. The futures that rely on the runtime-specific I/O or timers
+/// (e.g. `tokio::time::sleep`) need the runtime that doesn't require its context to be entered,
+/// like `async-std` or `smol`. The [`TokioSpawner`](crate::TokioSpawner) uses a `tokio::task::LocalSet` instead.
+pub(crate) struct LocalExecutor {
+    sender: mpsc::Sender<Message>,
+}
+
+impl LocalExecutor {
+    /// Returns the executor shared by the whole application, starting it on the first call.
+    pub(crate) fn global() -> &'static LocalExecutor {
+        static GLOBAL: OnceLock<LocalExecutor> = OnceLock::new();
+        GLOBAL.get_or_init(LocalExecutor::start)
+    }
+
+    fn start() -> Self {
+        let (sender, receiver) = mpsc::channel::<Message>();
+        let wake_sender: mpsc::Sender<Message> = sender.clone();
+        std::thread::Builder::new()
+            .name("tauriless-local".to_string())
+            .spawn(move || run(receiver, wake_sender))
+            .expect("Failed to spawn the thread of the `tauriless` local executor.");
+        Self { sender }
+    }
+
+    /// Returns the closure back if the thread of the executor is no longer running.
+    pub(crate) fn spawn(&self, f: SpawnedLocal) -> Result<(), SpawnedLocal> {
+        self.sender.send(Message::Spawn(f)).map_err(|e| match e.0 {
+            Message::Spawn(f) => f,
+            Message::Wake(_) => unreachable!("the sent message spawns the task"),
+        })
+    }
+}
+
+struct TaskWaker {
+    id: usize,
+    sender: mpsc::Sender<Message>,
+}
+
+impl Wake for TaskWaker {
+    fn wake(self: Arc<Self>) {
+        let _ = self.sender.send(Message::Wake(self.id));
+    }
+}
+
+fn run(receiver: mpsc::Receiver<Message>, wake_sender: mpsc::Sender<Message>) {
+    let mut tasks: HashMap<usize, Pin<Box<dyn Future<Output = ()>>>> = HashMap::new();
+    let mut next_id: usize = 0;
+    for message in receiver {
+        let id: usize = match message {
+            Message::Spawn(f) => {
+                // The panics must not stop the thread, or the later tasks would never be run.
+                let Ok(task) = catch_unwind(f) else {
+                    continue;
+                };
+                let id: usize = next_id;
+                next_id = next_id.wrapping_add(1);
+                tasks.insert(id, task);
+                id
+            }
+            Message::Wake(id) => id,
+        };
+        // The task may have been completed before the wake-up arrived.
+        let Some(task) = tasks.get_mut(&id) else {
+            continue;
+        };
+        let waker = Waker::from(Arc::new(TaskWaker {
+            id,
+            sender: wake_sender.clone(),
+        }));
+        // The task that panicked is dropped just like the completed one.
+        let poll = catch_unwind(|| task.as_mut().poll(&mut Context::from_waker(&waker)));
+        if !matches!(poll, Ok(Poll::Pending)) {
+            let task = tasks.remove(&id);
+            let _ = catch_unwind(move || drop(task));
+        }
+    }
+}
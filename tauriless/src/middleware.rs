@@ -0,0 +1,175 @@
+use std::borrow::Cow;
+use std::sync::Arc;
+
+use wry::http::{header, HeaderMap, HeaderValue, Response, Uri};
+
+use crate::registry::Entry;
+use crate::router::invoke_entry;
+use crate::{Config, Responder};
+
+/// The cross-cutting behaviour, e.g. logging, timing, auth checks or feature flags, applied to every command
+/// of a [`CommandRegistry`](crate::CommandRegistry). See [`CommandRegistry::middleware`](crate::CommandRegistry::middleware).
+///
+/// The middleware receives the [`Call`] of a command, whose name is already resolved but whose arguments are
+/// not yet deserialized, and either passes it to the [`Next`] middleware, possibly after changing its body or
+/// the way its response is treated, or responds to it without invoking the command.
+///
+/// The middleware is called on the thread dispatching the request, e.g. the thread of the event loop, so it
+/// shouldn't block. It may hand the call over to another thread or task and pass it on from there. If the middleware
+/// panics on the dispatching thread before the call is answered, the call is answered with `500 Internal Server Error`.
+///
+/// ## Example
+///
+/// ```rust
+/// use std::time::Instant;
+///
+/// use tauriless::{command, commands, Call, Next};
+///
+/// #[command]
+/// fn add(a: i32, b: i32) -> i32 {
+///     a + b
+/// }
+///
+/// let mut commands = commands!(add);
+/// commands.middleware(|call: Call, next: Next| {
+///     let command: String = call.command().to_string();
+///     let start = Instant::now();
+///     next.run(call.map_response(move |response| {
+///         println!("`{command}` responded with {} in {:?}", response.status(), start.elapsed());
+///         response
+///     }));
+/// });
+/// ```
+pub trait Middleware: Send + Sync + 'static {
+    /// Handles the call, either passing it to `next` or responding to it with [`Call::respond`].
+    fn handle(&self, call: Call, next: Next);
+}
+
+impl<F> Middleware for F
+where
+    F: Fn(Call, Next) + Send + Sync + 'static,
+{
+    fn handle(&self, call: Call, next: Next) {
+        self(call, next)
+    }
+}
+
+/// The invocation of a command passed through the [`Middleware`]s.
+pub struct Call {
+    command: String,
+    headers: HeaderMap,
+    body: Vec<u8>,
+    responder: Responder,
+}
+
+impl Call {
+    pub(crate) fn new(
+        command: String,
+        headers: HeaderMap,
+        body: Vec<u8>,
+        responder: Responder,
+    ) -> Self {
+        Self {
+            command,
+            headers,
+            body,
+            responder,
+        }
+    }
+
+    /// Returns the name of the invoked command as in the URL, e.g. `get-document` or `plugin:fs|read`.
+    pub fn command(&self) -> &str {
+        &self.command
+    }
+
+    /// Returns the headers of the request.
+    pub fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+
+    /// Returns the encoded arguments of the command, which can be decoded with
+    /// [`slice_to_deserialize`](crate::slice_to_deserialize).
+    pub fn body(&self) -> &[u8] {
+        &self.body
+    }
+
+    /// Returns the encoded arguments of the command for replacing them, e.g. with the ones encoded with
+    /// [`serialize_to_vec_u8`](crate::serialize_to_vec_u8).
+    pub fn body_mut(&mut self) -> &mut Vec<u8> {
+        &mut self.body
+    }
+
+    /// Transforms the response to the call once it's sent, e.g. to record it or to add a header.
+    pub fn map_response<F>(mut self, f: F) -> Self
+    where
+        F: FnOnce(Response<Cow<'static, [u8]>>) -> Response<Cow<'static, [u8]>> + Send + 'static,
+    {
+        let responder: Responder = self.responder;
+        self.responder = Responder::new(move |response| responder.respond(f(response)));
+        self
+    }
+
+    /// Responds to the call without invoking the command, e.g. with `403 Forbidden`.
+    ///
+    /// Just like the responses of the commands, the response allows any origin to read it, unless it sets
+    /// `Access-Control-Allow-Origin` itself.
+    pub fn respond(self, mut response: Response<Cow<'static, [u8]>>) {
+        response
+            .headers_mut()
+            .entry(header::ACCESS_CONTROL_ALLOW_ORIGIN)
+            .or_insert(HeaderValue::from_static("*"));
+        self.responder.respond(response)
+    }
+
+    pub(crate) fn into_parts(self) -> (String, HeaderMap, Vec<u8>, Responder) {
+        (self.command, self.headers, self.body, self.responder)
+    }
+}
+
+impl std::fmt::Debug for Call {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Call")
+            .field("command", &self.command)
+            .field("headers", &self.headers)
+            .finish_non_exhaustive()
+    }
+}
+
+/// The rest of the [`Middleware`]s followed by the invocation of the command.
+pub struct Next {
+    config: Arc<Config>,
+    entry: Entry,
+    uri: Uri,
+    // The index of the next middleware of the entry.
+    index: usize,
+}
+
+impl Next {
+    pub(crate) fn new(config: Arc<Config>, entry: Entry, uri: Uri) -> Self {
+        Self {
+            config,
+            entry,
+            uri,
+            index: 0,
+        }
+    }
+
+    /// Passes the call to the next middleware or, if there's none, invokes the command.
+    pub fn run(mut self, call: Call) {
+        match self.entry.middlewares.get(self.index).cloned() {
+            Some(middleware) => {
+                self.index += 1;
+                middleware.handle(call, self)
+            }
+            None => invoke_entry(&self.config, &self.entry, &self.uri, call),
+        }
+    }
+}
+
+impl std::fmt::Debug for Next {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Next")
+            .field("remaining", &(self.entry.middlewares.len() - self.index))
+            .finish_non_exhaustive()
+    }
+}
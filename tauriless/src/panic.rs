@@ -0,0 +1,62 @@
+use std::any::Any;
+use std::future::Future;
+use std::panic::AssertUnwindSafe;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// The payload of a caught panic, as returned by [`std::panic::catch_unwind`].
+pub(crate) type PanicPayload = Box<dyn Any + Send + 'static>;
+
+/// Invokes the closure, catching the panic if it occurs.
+///
+/// The commands are not required to be [`UnwindSafe`](std::panic::UnwindSafe). The state they could have left
+/// broken is either owned by the command or is the managed state, which is shared across the commands
+/// and therefore must be robust to the failures anyway (e.g. via the poisoning of the `Mutex`).
+#[doc(hidden)]
+pub fn catch_unwind<F, R>(f: F) -> Result<R, PanicPayload>
+where
+    F: FnOnce() -> R,
+{
+    std::panic::catch_unwind(AssertUnwindSafe(f))
+}
+
+/// The future catching the panics that occur while polling the inner future.
+///
+/// The inner future is boxed so that it can be polled without `unsafe` pin projections.
+#[doc(hidden)]
+pub struct CatchUnwind<F: Future> {
+    inner: Pin<Box<F>>,
+}
+
+impl<F: Future> CatchUnwind<F> {
+    pub fn new(inner: F) -> Self {
+        Self {
+            inner: Box::pin(inner),
+        }
+    }
+}
+
+impl<F: Future> Future for CatchUnwind<F> {
+    type Output = Result<F::Output, PanicPayload>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let inner: Pin<&mut F> = self.inner.as_mut();
+        match catch_unwind(move || inner.poll(cx)) {
+            Ok(Poll::Pending) => Poll::Pending,
+            Ok(Poll::Ready(output)) => Poll::Ready(Ok(output)),
+            Err(payload) => Poll::Ready(Err(payload)),
+        }
+    }
+}
+
+/// Returns the message of the panic, if the payload is a string.
+///
+/// The payloads of `panic!("literal")` and `panic!("{}", formatted)` are `&'static str` and `String`
+/// respectively. Other payloads can only be produced with [`std::panic::panic_any`].
+pub(crate) fn panic_message(payload: &PanicPayload) -> Option<&str> {
+    if let Some(message) = payload.downcast_ref::<&'static str>() {
+        Some(message)
+    } else {
+        payload.downcast_ref::<String>().map(String::as_str)
+    }
+}
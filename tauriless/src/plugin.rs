@@ -0,0 +1,65 @@
+use crate::{CommandRegistry, StateManager};
+
+/// The reusable bundle of the `tauriless` commands, e.g. the filesystem helpers shared across applications.
+///
+/// The plugin is added to the [`CommandRegistry`] with [`CommandRegistry::plugin`], so it's attached to the
+/// [`wry::WebViewBuilder`] via [`WebViewBuilderExt`](crate::WebViewBuilderExt) and its commands are dispatched
+/// just like the other ones. The command `read` of the plugin `fs` is invoked as `plugin:fs|read`, e.g.
+/// `invoke("plugin:fs|read", ["notes.txt"])` from `tauriless-js`.
+///
+/// ## Example
+///
+/// ```rust
+/// use std::path::PathBuf;
+/// use tauriless::{command, commands, CommandRegistry, Plugin, State, StateManager};
+///
+/// struct Root(PathBuf);
+///
+/// #[command]
+/// fn read(path: String, root: State<Root>) -> Result<String, String> {
+///     std::fs::read_to_string(root.0.join(path)).map_err(|e| e.to_string())
+/// }
+///
+/// struct FsPlugin {
+///     root: PathBuf,
+/// }
+///
+/// impl Plugin for FsPlugin {
+///     fn name(&self) -> &'static str {
+///         "fs"
+///     }
+///
+///     fn commands(&self) -> CommandRegistry {
+///         commands!(read)
+///     }
+///
+///     fn state(&self) -> StateManager {
+///         StateManager::new().with(Root(self.root.clone()))
+///     }
+/// }
+///
+/// let mut registry = CommandRegistry::new();
+/// registry.plugin(FsPlugin { root: PathBuf::from("data") });
+/// assert!(registry.contains("plugin:fs|read"));
+/// ```
+pub trait Plugin: Send + 'static {
+    /// The name of the plugin, which is the namespace of its commands.
+    fn name(&self) -> &'static str;
+
+    /// The commands of the plugin.
+    fn commands(&self) -> CommandRegistry;
+
+    /// The state available to the commands of the plugin taking [`State`](crate::State) arguments.
+    ///
+    /// The plugin doesn't have access to the state of the application, and vice versa, so the types
+    /// of the state can't clash. By default, the state is empty.
+    fn state(&self) -> StateManager {
+        StateManager::new()
+    }
+
+    /// Called with the state of the plugin once the commands are attached to the [`wry::WebViewBuilder`],
+    /// before any of them is invoked. By default, it does nothing.
+    fn setup(&mut self, state: &StateManager) {
+        let _ = state;
+    }
+}
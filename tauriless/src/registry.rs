@@ -0,0 +1,260 @@
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock};
+
+use crate::panic::PanicPayload;
+use crate::throttle::Throttle;
+use crate::Middleware;
+use crate::{
+    catch_unwind, handle_command_panic, handle_deserialization_error, Command, Config, Plugin,
+    Responder, StateManager,
+};
+
+/// The function handling the request invoking a command.
+pub(crate) type Handler =
+    Arc<dyn Fn(&Arc<Config>, &wry::http::Uri, Vec<u8>, Responder) + Send + Sync>;
+
+/// The registered command: the function handling the requests, the names of the arguments and the limits
+/// of the invocations.
+#[derive(Clone)]
+pub(crate) struct Entry {
+    pub(crate) handler: Handler,
+    /// See [`Command::ARG_NAMES`]. It's empty for the closures, whose arguments have no names.
+    pub(crate) arg_names: &'static [&'static str],
+    /// See [`Command::MAX_CONCURRENCY`] and [`Command::RATE_LIMIT`]. It's `None` for the closures.
+    pub(crate) throttle: Option<Arc<Throttle>>,
+    /// The middlewares applied to the command, the outermost first.
+    pub(crate) middlewares: Arc<[Arc<dyn Middleware>]>,
+}
+
+impl Entry {
+    // Wraps the command in the middlewares, which are applied before the ones it already has.
+    fn wrap(&mut self, middlewares: &[Arc<dyn Middleware>]) {
+        if !middlewares.is_empty() {
+            self.middlewares = middlewares
+                .iter()
+                .chain(self.middlewares.iter())
+                .cloned()
+                .collect();
+        }
+    }
+}
+
+/// The function setting up a [`Plugin`] when the commands are attached to the builder.
+type Setup = Box<dyn FnOnce(&Config) + Send>;
+
+/// The set of the `tauriless` commands, keyed by name.
+///
+/// Unlike the set created with [`commands!`](crate::commands!), it can be built at runtime, e.g. to register
+/// the commands conditionally or to merge the commands from different crates. It's passed to
+/// [`with_tauriless_commands`](crate::WebViewBuilderExt::with_tauriless_commands) and its counterparts
+/// just like the result of [`commands!`](crate::commands!), which is a [`CommandRegistry`] as well.
+///
+/// ## Example
+///
+/// ```rust
+/// use tauriless::{command, commands, CommandRegistry};
+///
+/// #[command]
+/// fn add(a: i32, b: i32) -> i32 {
+///     a + b
+/// }
+///
+/// #[command]
+/// async fn debug_info() -> String {
+///     "debug".to_string()
+/// }
+///
+/// let mut registry = CommandRegistry::new();
+/// registry
+///     .register::<__command_add>()
+///     .register_fn("greet", |(name,): (String,)| format!("Hello, {name}!"));
+/// if cfg!(debug_assertions) {
+///     registry.merge(commands!(debug_info));
+/// }
+/// assert!(registry.contains("add"));
+/// assert!(registry.contains("greet"));
+/// ```
+#[derive(Default)]
+pub struct CommandRegistry {
+    handlers: HashMap<String, Entry>,
+    setups: Vec<Setup>,
+    middlewares: Vec<Arc<dyn Middleware>>,
+}
+
+impl CommandRegistry {
+    /// Creates an empty [`CommandRegistry`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers the command generated by [`#[command]`](crate::command). For the function `foo`, it's
+    /// the type `__command_foo`.
+    ///
+    /// The command replaces the previously registered command with the same name, if any.
+    pub fn register<C: Command + 'static>(&mut self) -> &mut Self {
+        let entry = Entry {
+            handler: Arc::new(C::handle),
+            arg_names: C::ARG_NAMES,
+            throttle: Throttle::new(C::MAX_CONCURRENCY, C::RATE_LIMIT),
+            middlewares: Arc::new([]),
+        };
+        self.handlers.insert(C::URL_NAME.to_string(), entry);
+        self
+    }
+
+    /// Registers the closure as the synchronous command with the given name.
+    ///
+    /// The arguments are deserialized as `A` whether they're supplied as an array, e.g. into a tuple,
+    /// or as an object, e.g. into a struct. The return value is serialized as is, so, unlike the
+    /// commands generated by [`#[command]`](crate::command), the closures returning `Result<T, E>`
+    /// are not treated specially. Just like the synchronous commands, the closure is run on the thread
+    /// of the event loop.
+    ///
+    /// The closure replaces the previously registered command with the same name, if any.
+    pub fn register_fn<A, R, F>(&mut self, name: &str, f: F) -> &mut Self
+    where
+        A: for<'a> serde::Deserialize<'a>,
+        R: serde::Serialize,
+        F: Fn(A) -> R + Send + Sync + 'static,
+    {
+        use tauriless_serde::PositionalOrNamed;
+
+        let cmd_name: String = name.to_string();
+        let handler = move |_config: &Arc<Config>,
+                            _uri: &wry::http::Uri,
+                            body: Vec<u8>,
+                            responder: Responder| {
+            // The closure has no arity, so any number of the positional arguments is accepted.
+            let args = tauriless_serde::slice_to_deserialize_args::<A, A>(&body, 0..=usize::MAX);
+            let args: A = match args {
+                Ok(PositionalOrNamed::Positional(args) | PositionalOrNamed::Named(args)) => args,
+                Err(e) => return responder.respond(handle_deserialization_error(&cmd_name, e)),
+            };
+            let ret: Result<R, PanicPayload> = catch_unwind(|| f(args));
+            match ret {
+                Ok(ret) => responder.respond(crate::handlers::handle_ok(&ret)),
+                Err(payload) => responder.respond(handle_command_panic(&cmd_name, payload)),
+            }
+        };
+        let entry = Entry {
+            handler: Arc::new(handler),
+            arg_names: &[],
+            throttle: None,
+            middlewares: Arc::new([]),
+        };
+        self.handlers.insert(name.replace('_', "-"), entry);
+        self
+    }
+
+    /// Moves the commands of the other registry, including the ones of its plugins, into this one.
+    /// The commands of the other registry replace the commands with the same names, if any.
+    ///
+    /// The [`Middleware`]s of the other registry keep applying only to its commands, inside of the middlewares
+    /// of this one.
+    pub fn merge(&mut self, other: CommandRegistry) -> &mut Self {
+        let mut handlers: HashMap<String, Entry> = other.handlers;
+        for entry in handlers.values_mut() {
+            entry.wrap(&other.middlewares);
+        }
+        self.handlers.extend(handlers);
+        self.setups.extend(other.setups);
+        self
+    }
+
+    /// Registers the commands of the [`Plugin`] under its namespace, e.g. `plugin:fs|read` for the command
+    /// `read` of the plugin `fs`.
+    ///
+    /// The commands of the plugin have access only to the state of the plugin. The plugin is set up when
+    /// the registry is attached to the [`wry::WebViewBuilder`].
+    pub fn plugin<P: Plugin>(&mut self, mut plugin: P) -> &mut Self {
+        let prefix: String = format!("plugin:{}|", plugin.name().replace('_', "-"));
+        let commands: CommandRegistry = plugin.commands();
+        // The config of the plugin is created once the config of the application is known.
+        let plugin_config: Arc<OnceLock<Arc<Config>>> = Arc::new(OnceLock::new());
+        for (
+            name,
+            Entry {
+                handler,
+                arg_names,
+                throttle,
+                middlewares,
+            },
+        ) in commands.handlers
+        {
+            let plugin_config: Arc<OnceLock<Arc<Config>>> = plugin_config.clone();
+            let handler = move |_config: &Arc<Config>,
+                                uri: &wry::http::Uri,
+                                body: Vec<u8>,
+                                responder: Responder| {
+                let config: &Arc<Config> = plugin_config
+                    .get()
+                    .expect("The plugins are set up before the commands are invoked.");
+                handler(config, uri, body, responder)
+            };
+            let mut entry = Entry {
+                handler: Arc::new(handler),
+                arg_names,
+                throttle,
+                middlewares,
+            };
+            // The middlewares of the plugin apply only to its commands.
+            entry.wrap(&commands.middlewares);
+            self.handlers.insert(format!("{prefix}{name}"), entry);
+        }
+        // The nested plugins are set up along with this one.
+        self.setups.extend(commands.setups);
+        let state: StateManager = plugin.state();
+        self.setups.push(Box::new(move |config: &Config| {
+            plugin.setup(&state);
+            let _ = plugin_config.set(Arc::new(config.for_plugin(state)));
+        }));
+        self
+    }
+
+    /// Applies the [`Middleware`] to all the commands of the registry, including the ones registered or merged
+    /// later and the ones of its plugins.
+    ///
+    /// The middlewares are applied in the order they're added, the first one being the outermost. They apply
+    /// once the name of the command is resolved, before the arguments are checked against the
+    /// [`Limits`](crate::Limits), the [`Capabilities`](crate::Capabilities) and the limits of the invocations
+    /// set with `#[command(max_concurrency = ..., rate_limit = "...")]`.
+    pub fn middleware<M: Middleware>(&mut self, middleware: M) -> &mut Self {
+        self.middlewares.push(Arc::new(middleware));
+        self
+    }
+
+    /// Returns `true` if the command with the given name is registered.
+    pub fn contains(&self, name: &str) -> bool {
+        self.handlers.contains_key(&name.replace('_', "-"))
+    }
+
+    /// Returns the number of the registered commands.
+    pub fn len(&self) -> usize {
+        self.handlers.len()
+    }
+
+    /// Returns `true` if no commands are registered.
+    pub fn is_empty(&self) -> bool {
+        self.handlers.is_empty()
+    }
+}
+
+impl CommandRegistry {
+    /// Sets up the plugins and returns the registered commands, keyed by the URL name.
+    pub(crate) fn into_handlers(self, config: &Config) -> HashMap<String, Entry> {
+        for setup in self.setups {
+            setup(config);
+        }
+        let mut handlers: HashMap<String, Entry> = self.handlers;
+        for entry in handlers.values_mut() {
+            entry.wrap(&self.middlewares);
+        }
+        handlers
+    }
+}
+
+impl std::fmt::Debug for CommandRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_set().entries(self.handlers.keys()).finish()
+    }
+}
@@ -0,0 +1,312 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+
+use tauriless_common::url::CANCELLATION_PATH;
+use wry::http::{HeaderMap, Request, Response, Uri};
+
+use crate::handlers::{
+    handle_command_forbidden, handle_dispatch_panic, handle_forbidden_origin,
+    handle_limit_exceeded, handle_preflight, handle_too_many_requests, handle_unauthorized,
+};
+use crate::middleware::{Call, Next};
+use crate::registry::Entry;
+use crate::security::OriginCheck;
+use crate::{catch_unwind, handle_cancellation, handle_unknown_command, CommandRegistry, Config};
+
+type Respond = Box<dyn FnOnce(Response<Cow<'static, [u8]>>) + Send>;
+
+/// The callback responding to the request invoking a command.
+#[doc(hidden)]
+pub struct Responder(Respond);
+
+impl Responder {
+    pub fn new<F>(f: F) -> Self
+    where
+        F: FnOnce(Response<Cow<'static, [u8]>>) + Send + 'static,
+    {
+        Self(Box::new(f))
+    }
+
+    pub fn respond(self, response: Response<Cow<'static, [u8]>>) {
+        (self.0)(response)
+    }
+}
+
+/// The [`Responder`] kept aside while the request is handled by the code that may panic, so that the request
+/// can still be answered after the panic unless it has been answered already.
+#[derive(Clone)]
+pub(crate) struct SharedResponder(Arc<Mutex<Option<Responder>>>);
+
+impl SharedResponder {
+    pub(crate) fn new(responder: Responder) -> Self {
+        Self(Arc::new(Mutex::new(Some(responder))))
+    }
+
+    /// Returns the [`Responder`] responding with the shared one.
+    pub(crate) fn responder(&self) -> Responder {
+        let shared: SharedResponder = self.clone();
+        Responder::new(move |response| shared.respond(response))
+    }
+
+    /// Responds unless the request has been answered already.
+    pub(crate) fn respond(&self, response: Response<Cow<'static, [u8]>>) {
+        let responder: Option<Responder> = self.0.lock().unwrap_or_else(|e| e.into_inner()).take();
+        if let Some(responder) = responder {
+            responder.respond(response);
+        }
+    }
+}
+
+/// The dispatcher of the requests invoking the `tauriless` commands, independent of the host serving them.
+///
+/// [`WebViewBuilderExt`](crate::WebViewBuilderExt) serves the requests sent by `tauriless-js` with the
+/// [`Router`] created from the given commands. The [`Router`] can also be mounted in other hosts, or used
+/// to invoke the commands without a window, e.g. in tests.
+///
+/// The synchronous commands are run by the caller of [`Router::handle`] or [`Router::dispatch`], while the
+/// asynchronous and the blocking commands are spawned by the [`Spawner`](crate::Spawner) of the [`Config`].
+///
+/// ## Example
+///
+/// ```rust
+/// use tauriless::{command, commands, Config, Router};
+/// use tauriless::http::Request;
+///
+/// #[command]
+/// fn add(a: i32, b: i32) -> i32 {
+///     a + b
+/// }
+///
+/// let router = Router::new(commands!(add), Config::new());
+/// let request = Request::post("http://tauiriless.localhost/add")
+///     .body(tauriless::serialize_to_vec_u8(&(1, 2)).unwrap())
+///     .unwrap();
+/// # let response = tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+/// let response = router.handle(request).await;
+/// # response });
+/// assert_eq!(response.status(), 200);
+/// let sum: i32 = tauriless::slice_to_deserialize(response.body()).unwrap();
+/// assert_eq!(sum, 3);
+/// ```
+#[derive(Clone)]
+pub struct Router {
+    handlers: Arc<HashMap<String, Entry>>,
+    config: Arc<Config>,
+}
+
+impl Router {
+    /// Creates the [`Router`] dispatching the requests to the given commands, setting up their plugins.
+    pub fn new(commands: CommandRegistry, config: Config) -> Self {
+        let handlers: HashMap<String, Entry> = commands.into_handlers(&config);
+        Self {
+            handlers: Arc::new(handlers),
+            config: Arc::new(config),
+        }
+    }
+
+    /// Handles the request invoking a command, whose name is the path of the URL, and returns the response.
+    pub async fn handle(&self, request: Request<Vec<u8>>) -> Response<Vec<u8>> {
+        let (sender, receiver) = oneshot();
+        self.dispatch(request, move |response| sender.send(response));
+        let response: Response<Cow<'static, [u8]>> = receiver.await;
+        response.map(Cow::into_owned)
+    }
+
+    /// The callback-based counterpart of [`Router::handle`] for the hosts like
+    /// [`wry`](wry::WebViewBuilder::with_asynchronous_custom_protocol). The callback is called exactly once,
+    /// possibly on another thread.
+    pub fn dispatch<F>(&self, request: Request<Vec<u8>>, respond: F)
+    where
+        F: FnOnce(Response<Cow<'static, [u8]>>) + Send + 'static,
+    {
+        let (parts, body): (wry::http::request::Parts, Vec<u8>) = request.into_parts();
+        let responder = match self.config.security().check_origin(&parts.headers) {
+            OriginCheck::Any => Responder::new(respond),
+            OriginCheck::Allowed(origin) => Responder::new(move |mut response| {
+                // The handlers allow any origin, so only the allowed one is echoed back instead.
+                let headers = response.headers_mut();
+                headers.insert(wry::http::header::ACCESS_CONTROL_ALLOW_ORIGIN, origin);
+                headers.append(
+                    wry::http::header::VARY,
+                    wry::http::HeaderValue::from_static("origin"),
+                );
+                respond(response)
+            }),
+            OriginCheck::Forbidden => return respond(handle_forbidden_origin()),
+        };
+        // The preflight requests don't carry the auth token.
+        if parts.method == wry::http::Method::OPTIONS {
+            return responder.respond(handle_preflight());
+        }
+        if !self.config.security().check_auth_token(&parts.headers) {
+            return responder.respond(handle_unauthorized());
+        }
+        let uri: Uri = parts.uri;
+        let path: &str = uri.path().trim_start_matches('/');
+        if path == CANCELLATION_PATH {
+            return responder.respond(handle_cancellation(self.config.cancel_invocation(&uri)));
+        }
+        let path: String = percent_decode(path).into_owned();
+        let Some(entry) = self.handlers.get(&path) else {
+            return responder.respond(handle_unknown_command(&path));
+        };
+        // Unlike the commands, the middlewares and the spawning of the commands aren't guarded otherwise,
+        // and the panic must not unwind through the event loop.
+        let shared = SharedResponder::new(responder);
+        let call = Call::new(path.clone(), parts.headers, body, shared.responder());
+        let next = Next::new(self.config.clone(), entry.clone(), uri);
+        if let Err(payload) = catch_unwind(move || next.run(call)) {
+            shared.respond(handle_dispatch_panic(&path, payload));
+        }
+    }
+}
+
+// Invokes the command once the call passed through the middlewares, unless the call is rejected.
+pub(crate) fn invoke_entry(config: &Arc<Config>, entry: &Entry, uri: &Uri, call: Call) {
+    let (path, headers, body, responder): (String, HeaderMap, Vec<u8>, Responder) =
+        call.into_parts();
+    match tauriless_serde::check_limits(&body, config.limits()) {
+        // The malformed arguments are reported by the command, which knows what it expects.
+        Ok(()) | Err(tauriless_serde::check_limits::Error::FromSliceError(_)) => {}
+        Err(e) => return responder.respond(handle_limit_exceeded(&path, e)),
+    }
+    if let Some(capabilities) = config.capabilities() {
+        let granted: Option<&str> = capabilities.check(&path, entry.arg_names, &headers, &body);
+        if granted.is_none() {
+            return responder.respond(handle_command_forbidden(&path));
+        }
+    }
+    let responder: Responder = match entry.throttle.as_ref().map(|throttle| throttle.acquire()) {
+        None => responder,
+        // The invocation is running until it's answered, whether it completed, failed or was cancelled.
+        Some(Ok(permit)) => Responder::new(move |response| {
+            drop(permit);
+            responder.respond(response)
+        }),
+        Some(Err(throttled)) => {
+            return responder.respond(handle_too_many_requests(&path, throttled))
+        }
+    };
+    (entry.handler)(config, uri, body, responder)
+}
+
+impl Router {
+    /// Returns the limits of the arguments set with [`Config::with_limits`].
+    #[cfg(feature = "dev-server")]
+    pub(crate) fn limits(&self) -> &tauriless_serde::Limits {
+        self.config.limits()
+    }
+
+    /// Returns the per-launch secret required by the [`Security`](crate::Security) of the [`Config`], if any.
+    #[cfg(feature = "testing")]
+    pub(crate) fn auth_token(&self) -> Option<&str> {
+        self.config.security().auth_token()
+    }
+}
+
+impl std::fmt::Debug for Router {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Router")
+            .field("commands", &self.handlers.keys())
+            .finish_non_exhaustive()
+    }
+}
+
+// The WebView may percent-encode the characters of the names of the commands of the plugins, like `|`.
+pub(crate) fn percent_decode(path: &str) -> Cow<'_, str> {
+    if !path.contains('%') {
+        return Cow::Borrowed(path);
+    }
+    let bytes: &[u8] = path.as_bytes();
+    let mut decoded: Vec<u8> = Vec::with_capacity(bytes.len());
+    let mut i: usize = 0;
+    while i < bytes.len() {
+        let hex: Option<u8> = bytes
+            .get(i + 1..i + 3)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+    Cow::Owned(String::from_utf8_lossy(&decoded).into_owned())
+}
+
+#[derive(Default)]
+struct Slot {
+    response: Option<Response<Cow<'static, [u8]>>>,
+    // Whether the sender was dropped, with or without sending the response.
+    closed: bool,
+    waker: Option<Waker>,
+}
+
+fn oneshot() -> (Sender, Receiver) {
+    let slot: Arc<Mutex<Slot>> = Arc::new(Mutex::new(Slot::default()));
+    (Sender { slot: slot.clone() }, Receiver { slot })
+}
+
+struct Sender {
+    slot: Arc<Mutex<Slot>>,
+}
+
+impl Sender {
+    fn send(self, response: Response<Cow<'static, [u8]>>) {
+        self.slot.lock().unwrap().response = Some(response);
+        // The waker is woken up on drop.
+    }
+}
+
+impl Drop for Sender {
+    fn drop(&mut self) {
+        let waker: Option<Waker> = {
+            let mut slot = self.slot.lock().unwrap();
+            slot.closed = true;
+            slot.waker.take()
+        };
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
+
+struct Receiver {
+    slot: Arc<Mutex<Slot>>,
+}
+
+impl Future for Receiver {
+    type Output = Response<Cow<'static, [u8]>>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut slot = self.slot.lock().unwrap();
+        if let Some(response) = slot.response.take() {
+            return Poll::Ready(response);
+        }
+        if slot.closed {
+            // The responder is never dropped without responding, unless the spawner dropped the task.
+            let response = Response::builder()
+                .status(wry::http::StatusCode::INTERNAL_SERVER_ERROR)
+                .header(
+                    wry::http::header::ACCESS_CONTROL_ALLOW_ORIGIN,
+                    wry::http::HeaderValue::from_static("*"),
+                )
+                .body(Cow::<'static, [u8]>::Borrowed(
+                    b"Internal server error: the command was dropped without responding.",
+                ))
+                .unwrap();
+            return Poll::Ready(response);
+        }
+        slot.waker = Some(cx.waker().clone());
+        Poll::Pending
+    }
+}
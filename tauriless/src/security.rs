@@ -0,0 +1,153 @@
+use std::sync::Arc;
+
+use tauriless_common::auth::AUTH_TOKEN_HEADER;
+use wry::http::{header, HeaderMap, HeaderValue};
+
+/// The security configuration of the `tauriless` commands, passed to [`Config::with_security`](crate::Config::with_security).
+///
+/// By default, the commands can be invoked from any origin, e.g. from a third-party iframe or from a page
+/// the WebView navigated to. With [`Security::with_allowed_origin`], only the allowed origins can invoke them,
+/// and with [`Security::with_auth_token`], only the requests carrying the per-launch secret can.
+///
+/// ## Example
+///
+/// ```rust
+/// use tauriless::{Config, Security};
+///
+/// let config = Config::new().with_security(
+///     Security::new()
+///         .with_allowed_origin("http://tauriless-assets.localhost")
+///         .with_allowed_origin("tauriless-assets://localhost")
+///         .with_auth_token(),
+/// );
+/// ```
+#[derive(Clone, Default)]
+pub struct Security {
+    // `None` allows any origin.
+    allowed_origins: Option<Arc<[String]>>,
+    auth_token: Option<Arc<str>>,
+}
+
+/// The outcome of the check of the origin of a request.
+pub(crate) enum OriginCheck {
+    /// Any origin is allowed, so the response allows any origin as well.
+    Any,
+    /// The origin is allowed, so the response echoes it back.
+    Allowed(HeaderValue),
+    /// The origin is not allowed, or the request doesn't tell its origin.
+    Forbidden,
+}
+
+impl Security {
+    /// Creates the default [`Security`], which allows any origin.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Allows the requests from the given origin, e.g. `http://tauriless-assets.localhost` or
+    /// `http://localhost:5173`, and forbids the requests from the origins that are not allowed.
+    ///
+    /// The origin of a request is taken from its `Origin` header or, failing that, from its `Referer` header.
+    /// The requests that carry neither are forbidden. The responses to the allowed requests echo back
+    /// the origin in `Access-Control-Allow-Origin` instead of `*`.
+    pub fn with_allowed_origin(mut self, origin: impl Into<String>) -> Self {
+        let mut origins: Vec<String> = self
+            .allowed_origins
+            .as_deref()
+            .map(<[String]>::to_vec)
+            .unwrap_or_default();
+        origins.push(origin.into().trim_end_matches('/').to_string());
+        self.allowed_origins = Some(origins.into());
+        self
+    }
+
+    /// Requires the requests to carry the random secret generated for this launch of the application.
+    ///
+    /// [`WebViewBuilderExt`](crate::WebViewBuilderExt) injects the secret into the WebView with an initialization
+    /// script, and `tauriless-js` attaches it to every request. The requests without the valid secret are
+    /// rejected with `401 Unauthorized` before their arguments are deserialized.
+    ///
+    /// The initialization script runs in every document loaded in the WebView, including the pages it navigated
+    /// to, so the secret is meant to be combined with [`Security::with_allowed_origin`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the random number generator of the OS is unavailable.
+    pub fn with_auth_token(mut self) -> Self {
+        let mut secret = [0u8; 32];
+        getrandom::getrandom(&mut secret).expect("failed to generate the `tauriless` auth token");
+        let token: String = secret.iter().map(|byte| format!("{byte:02x}")).collect();
+        self.auth_token = Some(token.into());
+        self
+    }
+
+    /// Returns the per-launch secret required by [`Security::with_auth_token`], if any.
+    ///
+    /// It's useful for the frontends that can't receive it via the initialization script, which pass it
+    /// as the `authToken` option of `createClient` of `tauriless-js`.
+    pub fn auth_token(&self) -> Option<&str> {
+        self.auth_token.as_deref()
+    }
+
+    /// Checks whether the request with the given headers carries the valid auth token, if it's required.
+    pub(crate) fn check_auth_token(&self, headers: &HeaderMap) -> bool {
+        let Some(expected) = &self.auth_token else {
+            return true;
+        };
+        match headers.get(AUTH_TOKEN_HEADER) {
+            Some(token) => constant_time_eq(token.as_bytes(), expected.as_bytes()),
+            None => false,
+        }
+    }
+
+    /// Checks the origin of the request with the given headers.
+    pub(crate) fn check_origin(&self, headers: &HeaderMap) -> OriginCheck {
+        let Some(allowed_origins) = &self.allowed_origins else {
+            return OriginCheck::Any;
+        };
+        let origin: Option<&str> = match headers.get(header::ORIGIN) {
+            Some(origin) => origin.to_str().ok(),
+            None => headers
+                .get(header::REFERER)
+                .and_then(|referer| referer.to_str().ok())
+                .map(referer_origin),
+        };
+        match origin {
+            Some(origin) if allowed_origins.iter().any(|allowed| allowed == origin) => {
+                match HeaderValue::from_str(origin) {
+                    Ok(origin) => OriginCheck::Allowed(origin),
+                    Err(_) => OriginCheck::Forbidden,
+                }
+            }
+            _ => OriginCheck::Forbidden,
+        }
+    }
+}
+
+impl std::fmt::Debug for Security {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // The secret is not revealed.
+        f.debug_struct("Security")
+            .field("allowed_origins", &self.allowed_origins)
+            .field("auth_token", &self.auth_token.as_ref().map(|_| ".."))
+            .finish()
+    }
+}
+
+// The comparison doesn't short-circuit, so its duration doesn't reveal the length of the matching prefix.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (a, b)| acc | (a ^ b)) == 0
+}
+
+// The origin is the scheme and the authority of the URL, e.g. `http://localhost:5173` of
+// `http://localhost:5173/index.html`.
+fn referer_origin(referer: &str) -> &str {
+    let Some(scheme_end) = referer.find("://") else {
+        return referer;
+    };
+    let authority_start: usize = scheme_end + "://".len();
+    match referer[authority_start..].find(['/', '?', '#']) {
+        Some(authority_len) => &referer[..authority_start + authority_len],
+        None => referer,
+    }
+}
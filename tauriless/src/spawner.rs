@@ -0,0 +1,228 @@
+use std::future::Future;
+use std::pin::Pin;
+#[cfg(feature = "tokio")]
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+
+use crate::local_executor::LocalExecutor;
+
+/// The future spawned by the [`Spawner`] to run an asynchronous command and respond to the request.
+pub type SpawnedFuture = Pin<Box<dyn Future<Output = ()> + Send + 'static>>;
+
+/// The closure spawned by the [`Spawner`] to run a blocking command and respond to the request.
+pub type SpawnedBlocking = Box<dyn FnOnce() + Send + 'static>;
+
+/// The closure spawned by the [`Spawner`] to create the future running a `#[command(local)]` command.
+///
+/// The future is not `Send`, so it must be polled on the thread where the closure was called.
+pub type SpawnedLocal = Box<dyn FnOnce() -> Pin<Box<dyn Future<Output = ()>>> + Send + 'static>;
+
+/// The future returned by [`Spawner::sleep`].
+pub type Sleep = Pin<Box<dyn Future<Output = ()> + Send + 'static>>;
+
+/// The abstraction over the async runtime running the asynchronous and the blocking commands.
+///
+/// The spawned tasks respond to the requests themselves, so their handles are not needed and can be detached.
+///
+/// `tauriless` provides the implementations for
+///
+/// * [tokio](https://tokio.rs/): [`TokioSpawner`], behind the `tokio` feature (enabled by default);
+/// * [async-std](https://async.rs/): [`AsyncStdSpawner`], behind the `async-std` feature;
+/// * [smol](https://github.com/smol-rs/smol): [`SmolSpawner`], behind the `smol` feature.
+///
+/// The spawner is passed to [`Config::with_spawner`](crate::Config::with_spawner).
+pub trait Spawner: Send + Sync + 'static {
+    /// Spawns the future running an asynchronous command.
+    fn spawn(&self, future: SpawnedFuture);
+    /// Spawns the closure running a blocking command on a thread where blocking is acceptable.
+    fn spawn_blocking(&self, f: SpawnedBlocking);
+    /// Spawns the closure creating the future of a `#[command(local)]` command, which is not `Send`.
+    ///
+    /// By default, the future is driven by the single-threaded executor owned by `tauriless`.
+    ///
+    /// Returns the closure back if the executor is no longer running, so that the request is answered
+    /// with `500 Internal Server Error`.
+    fn spawn_local(&self, f: SpawnedLocal) -> Result<(), SpawnedLocal> {
+        LocalExecutor::global().spawn(f)
+    }
+    /// Returns the future that is completed after the given duration. It's used to time out the commands.
+    ///
+    /// By default, the time is measured by the timer thread owned by `tauriless`, so the future can be
+    /// polled by any runtime.
+    fn sleep(&self, duration: Duration) -> Sleep {
+        crate::timer::sleep(duration)
+    }
+}
+
+/// The [`Spawner`] spawning the tasks on the given [tokio](https://tokio.rs/) runtime.
+///
+/// Unlike [`tokio::spawn`], it doesn't require entering the runtime context with
+/// [`Runtime::enter`](tokio::runtime::Runtime::enter).
+///
+/// The futures of the `#[command(local)]` commands are spawned on a [`LocalSet`](tokio::task::LocalSet)
+/// driven by the dedicated thread, which is started on the first use.
+///
+/// The timeouts of the commands are measured by the timer of the runtime, so the runtime must be built with
+/// the timers enabled, e.g. with [`Builder::enable_all`](tokio::runtime::Builder::enable_all).
+#[cfg(feature = "tokio")]
+#[derive(Clone)]
+pub struct TokioSpawner {
+    handle: tokio::runtime::Handle,
+    local: Arc<OnceLock<tokio::sync::mpsc::UnboundedSender<SpawnedLocal>>>,
+}
+
+#[cfg(feature = "tokio")]
+impl TokioSpawner {
+    /// Creates the [`TokioSpawner`] spawning the tasks on the runtime with the given handle.
+    pub fn new(handle: tokio::runtime::Handle) -> Self {
+        Self {
+            handle,
+            local: Arc::new(OnceLock::new()),
+        }
+    }
+
+    fn start_local_set(&self) -> tokio::sync::mpsc::UnboundedSender<SpawnedLocal> {
+        let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel::<SpawnedLocal>();
+        let handle: tokio::runtime::Handle = self.handle.clone();
+        std::thread::Builder::new()
+            .name("tauriless-local".to_string())
+            .spawn(move || {
+                let local_set = tokio::task::LocalSet::new();
+                handle.block_on(local_set.run_until(async move {
+                    while let Some(f) = receiver.recv().await {
+                        tokio::task::spawn_local(f());
+                    }
+                }));
+            })
+            .expect("Failed to spawn the thread of the `tauriless` local executor.");
+        sender
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl std::fmt::Debug for TokioSpawner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("TokioSpawner").field(&self.handle).finish()
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl From<tokio::runtime::Handle> for TokioSpawner {
+    fn from(handle: tokio::runtime::Handle) -> Self {
+        Self::new(handle)
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl Spawner for TokioSpawner {
+    fn spawn(&self, future: SpawnedFuture) {
+        self.handle.spawn(future);
+    }
+
+    fn spawn_blocking(&self, f: SpawnedBlocking) {
+        self.handle.spawn_blocking(f);
+    }
+
+    fn spawn_local(&self, f: SpawnedLocal) -> Result<(), SpawnedLocal> {
+        let sender = self.local.get_or_init(|| self.start_local_set());
+        // The receiver is dropped if the thread driving the `LocalSet` has exited, e.g. with the runtime.
+        sender.send(f).map_err(|e| e.0)
+    }
+
+    fn sleep(&self, duration: Duration) -> Sleep {
+        // The timer of the runtime is looked up when the sleep is created, which requires its context.
+        let _guard = self.handle.enter();
+        Box::pin(tokio::time::sleep(duration))
+    }
+}
+
+/// The [`Spawner`] spawning the tasks on the global [async-std](https://async.rs/) runtime.
+#[cfg(feature = "async-std")]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct AsyncStdSpawner;
+
+#[cfg(feature = "async-std")]
+impl Spawner for AsyncStdSpawner {
+    fn spawn(&self, future: SpawnedFuture) {
+        async_std::task::spawn(future);
+    }
+
+    fn spawn_blocking(&self, f: SpawnedBlocking) {
+        async_std::task::spawn_blocking(f);
+    }
+
+    fn sleep(&self, duration: Duration) -> Sleep {
+        Box::pin(async_std::task::sleep(duration))
+    }
+}
+
+/// The [`Spawner`] spawning the tasks on the global [smol](https://github.com/smol-rs/smol) executor
+/// and running the blocking commands on its thread pool for blocking I/O.
+#[cfg(feature = "smol")]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SmolSpawner;
+
+#[cfg(feature = "smol")]
+impl Spawner for SmolSpawner {
+    fn spawn(&self, future: SpawnedFuture) {
+        smol::spawn(future).detach();
+    }
+
+    fn spawn_blocking(&self, f: SpawnedBlocking) {
+        smol::unblock(f).detach();
+    }
+
+    fn sleep(&self, duration: Duration) -> Sleep {
+        Box::pin(async move {
+            smol::Timer::after(duration).await;
+        })
+    }
+}
+
+/// The [`Spawner`] used when none was passed to [`Config::with_spawner`](crate::Config::with_spawner).
+///
+/// With the `tokio` feature, it spawns the tasks on the runtime whose context was entered with
+/// [`Runtime::enter`](tokio::runtime::Runtime::enter) on the thread of the event loop, which was the
+/// only option before the [`Spawner`] was introduced.
+#[derive(Default)]
+pub(crate) struct DefaultSpawner {
+    // The runtime is looked up on the first use rather than on the construction of the `Config`.
+    #[cfg(feature = "tokio")]
+    tokio: OnceLock<TokioSpawner>,
+}
+
+#[cfg(feature = "tokio")]
+impl DefaultSpawner {
+    fn tokio(&self) -> &TokioSpawner {
+        self.tokio.get_or_init(|| {
+            let handle = tokio::runtime::Handle::try_current().expect("Using async or blocking commands without a `tauriless::Spawner` requires entering the tokio runtime context prior to that. Either pass the spawner with `tauriless::Config::with_spawner` or use `let _rt_guard = rt.enter()` to enter the runtime context. See <https://docs.rs/tokio/latest/tokio/runtime/struct.Runtime.html#method.enter>.");
+            TokioSpawner::new(handle)
+        })
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl Spawner for DefaultSpawner {
+    fn spawn(&self, future: SpawnedFuture) {
+        self.tokio().spawn(future);
+    }
+
+    fn spawn_blocking(&self, f: SpawnedBlocking) {
+        self.tokio().spawn_blocking(f);
+    }
+
+    fn spawn_local(&self, f: SpawnedLocal) -> Result<(), SpawnedLocal> {
+        self.tokio().spawn_local(f)
+    }
+}
+
+#[cfg(not(feature = "tokio"))]
+impl Spawner for DefaultSpawner {
+    fn spawn(&self, _future: SpawnedFuture) {
+        panic!("Using async commands requires a `tauriless::Spawner`. Pass it with `tauriless::Config::with_spawner`.");
+    }
+
+    fn spawn_blocking(&self, _f: SpawnedBlocking) {
+        panic!("Using blocking commands requires a `tauriless::Spawner`. Pass it with `tauriless::Config::with_spawner`.");
+    }
+}
@@ -0,0 +1,119 @@
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// The storage for the application state that can be accessed by the commands via [`State`].
+///
+/// At most one value of each type can be managed.
+#[derive(Default)]
+pub struct StateManager {
+    map: HashMap<TypeId, Arc<dyn Any + Send + Sync>>,
+}
+
+impl StateManager {
+    /// Creates an empty [`StateManager`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the value to the managed state. Returns `false` and leaves the managed state intact
+    /// if a value of the same type is already managed.
+    pub fn manage<T>(&mut self, value: T) -> bool
+    where
+        T: Send + Sync + 'static,
+    {
+        let type_id = TypeId::of::<T>();
+        if self.map.contains_key(&type_id) {
+            return false;
+        }
+        self.map.insert(type_id, Arc::new(value));
+        true
+    }
+
+    /// The chainable counterpart of [`StateManager::manage`].
+    pub fn with<T>(mut self, value: T) -> Self
+    where
+        T: Send + Sync + 'static,
+    {
+        self.manage(value);
+        self
+    }
+
+    /// Returns the managed value of type `T`, if any.
+    pub fn try_get<T>(&self) -> Option<State<T>>
+    where
+        T: Send + Sync + 'static,
+    {
+        let value: Arc<dyn Any + Send + Sync> = self.map.get(&TypeId::of::<T>())?.clone();
+        // The type id was checked above, so the downcast can't fail.
+        let value: Arc<T> = value.downcast::<T>().ok()?;
+        Some(State(value))
+    }
+
+    /// Returns the managed value of type `T`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the value of type `T` is not managed.
+    pub fn get<T>(&self) -> State<T>
+    where
+        T: Send + Sync + 'static,
+    {
+        match self.try_get::<T>() {
+            Some(state) => state,
+            None => panic!(
+                "The state of type `{}` is not managed. Add it to the `tauriless::StateManager` passed to `with_tauriless_commands_and_state` or `tauriless::Config::with_state`.",
+                std::any::type_name::<T>()
+            ),
+        }
+    }
+}
+
+/// The argument type for the commands that need access to the application state.
+///
+/// The arguments of this type are not supplied by the frontend. Instead, they're filled from the
+/// [`StateManager`] passed to
+/// [`with_tauriless_commands_and_state`](crate::WebViewBuilderExt::with_tauriless_commands_and_state).
+///
+/// ## Example
+///
+/// ```rust
+/// use std::sync::atomic::{AtomicU32, Ordering};
+/// use tauriless::{command, State};
+///
+/// #[derive(Default)]
+/// struct Counter(AtomicU32);
+///
+/// #[command]
+/// fn increment(counter: State<Counter>, by: u32) -> u32 {
+///     counter.0.fetch_add(by, Ordering::Relaxed) + by
+/// }
+/// ```
+pub struct State<T: Send + Sync + 'static>(Arc<T>);
+
+impl<T: Send + Sync + 'static> State<T> {
+    /// Returns the inner [`Arc`].
+    pub fn inner(&self) -> &Arc<T> {
+        &self.0
+    }
+}
+
+impl<T: Send + Sync + 'static> Clone for State<T> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<T: Send + Sync + 'static> std::ops::Deref for State<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T: Send + Sync + 'static + std::fmt::Debug> std::fmt::Debug for State<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("State").field(&self.0).finish()
+    }
+}
@@ -0,0 +1,157 @@
+//! The harness for invoking the commands the way `tauriless-js` does, without a window.
+//!
+//! The arguments are encoded and the responses are decoded just like by `invoke` from `tauriless-js`,
+//! and the requests go through the same [`Router`] that serves the WebView, so the tests can run on
+//! the machines without a WebView, e.g. on headless CI.
+//!
+//! Available with the `testing` feature.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use serde_json::json;
+//! use tauriless::testing::{InvokeError, TestClient};
+//! use tauriless::{command, commands, Config};
+//!
+//! #[command]
+//! fn divide(a: i32, b: i32) -> Result<i32, String> {
+//!     a.checked_div(b).ok_or_else(|| "division by zero".to_string())
+//! }
+//!
+//! # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+//! let client = TestClient::new(commands!(divide), Config::new());
+//! assert_eq!(client.invoke("divide", json!([6, 3])).await.unwrap(), json!(2));
+//! assert_eq!(client.invoke("divide", json!({ "a": 6, "b": 2 })).await.unwrap(), json!(3));
+//! match client.invoke("divide", json!([1, 0])).await {
+//!     Err(InvokeError::Command(err)) => assert_eq!(err, json!("division by zero")),
+//!     other => panic!("unexpected result: {other:?}"),
+//! }
+//! # });
+//! ```
+
+use tauriless_common::auth::AUTH_TOKEN_HEADER;
+use tauriless_common::status::{COMMAND_CANCELLED, COMMAND_ERROR, COMMAND_TIMEOUT};
+use tauriless_common::url::command_to_url;
+
+use crate::http::{header, Request, Response, StatusCode};
+use crate::{CommandRegistry, Config, Router};
+
+/// The error of [`TestClient::invoke`], which corresponds to the rejection of the promise returned by
+/// `invoke` from `tauriless-js`.
+#[derive(thiserror::Error, Debug)]
+pub enum InvokeError {
+    /// The command returned `Err`. The promise is rejected with the `Err` value.
+    #[error("the command returned an error: {0}")]
+    Command(serde_json::Value),
+    /// The command didn't complete before its timeout. The promise is rejected with a `TimeoutError`.
+    #[error("{0}")]
+    Timeout(String),
+    /// The invocation of the command was cancelled.
+    #[error("the command was cancelled")]
+    Cancelled,
+    /// The request failed otherwise, e.g. the command is unknown, the arguments are invalid, or the command
+    /// panicked.
+    #[error("the request failed with status {status}: {message}")]
+    Status {
+        /// The status code of the response.
+        status: StatusCode,
+        /// The body of the response.
+        message: String,
+    },
+    /// The arguments couldn't be encoded.
+    #[error("failed to encode the arguments: {0}")]
+    Encode(#[from] tauriless_serde::serialize_to_vec_u8::Error),
+    /// The response couldn't be decoded.
+    #[error("failed to decode the response: {0}")]
+    Decode(#[from] tauriless_serde::slice_to_deserialize::Error),
+}
+
+/// The client invoking the commands through the [`Router`], as the frontend would.
+///
+/// The asynchronous and the blocking commands are spawned by the [`Spawner`](crate::Spawner) of the [`Config`],
+/// so the futures returned by [`TestClient::invoke`] are awaited within the corresponding runtime.
+#[derive(Clone, Debug)]
+pub struct TestClient {
+    router: Router,
+    origin: Option<String>,
+}
+
+impl TestClient {
+    /// Creates the [`TestClient`] invoking the given commands, setting up their plugins.
+    pub fn new(commands: CommandRegistry, config: Config) -> Self {
+        Self::from_router(Router::new(commands, config))
+    }
+
+    /// Creates the [`TestClient`] sending the requests to the given [`Router`].
+    pub fn from_router(router: Router) -> Self {
+        Self {
+            router,
+            origin: None,
+        }
+    }
+
+    /// Sends the requests with the given `Origin` header, e.g. to test the [`Security`](crate::Security)
+    /// of the [`Config`]. By default, the requests don't carry the header.
+    pub fn with_origin(mut self, origin: impl Into<String>) -> Self {
+        self.origin = Some(origin.into());
+        self
+    }
+
+    /// Invokes the command with the given name and arguments, e.g. `json!([1, 2])` or `json!({ "a": 1, "b": 2 })`,
+    /// and returns the decoded return value of the command.
+    ///
+    /// The arguments can be omitted with `serde_json::Value::Null`, which corresponds to
+    /// `invoke("command")` in `tauriless-js`.
+    pub async fn invoke(
+        &self,
+        command: &str,
+        args: serde_json::Value,
+    ) -> Result<serde_json::Value, InvokeError> {
+        self.invoke_as(command, args).await
+    }
+
+    /// Same as [`TestClient::invoke`] but decodes the return value of the command as `T`.
+    pub async fn invoke_as<T>(
+        &self,
+        command: &str,
+        args: serde_json::Value,
+    ) -> Result<T, InvokeError>
+    where
+        T: for<'a> serde::Deserialize<'a>,
+    {
+        let body: Vec<u8> = tauriless_serde::serialize_to_vec_u8(&args)?;
+        let mut request = Request::post(command_to_url(command));
+        if let Some(origin) = &self.origin {
+            request = request.header(header::ORIGIN, origin.as_str());
+        }
+        // The requests are sent by the frontend, which received the secret via the initialization script.
+        if let Some(token) = self.router.auth_token() {
+            request = request.header(AUTH_TOKEN_HEADER, token);
+        }
+        let request: Request<Vec<u8>> = request.body(body).unwrap();
+        let response: Response<Vec<u8>> = self.router.handle(request).await;
+        let status: StatusCode = response.status();
+        match status.as_u16() {
+            200 => Ok(tauriless_serde::slice_to_deserialize(response.body())?),
+            COMMAND_ERROR => Err(InvokeError::Command(tauriless_serde::slice_to_deserialize(
+                response.body(),
+            )?)),
+            COMMAND_TIMEOUT => Err(InvokeError::Timeout(message(&response))),
+            COMMAND_CANCELLED => Err(InvokeError::Cancelled),
+            _ => Err(InvokeError::Status {
+                status,
+                message: message(&response),
+            }),
+        }
+    }
+
+    /// Returns the [`Router`] the requests are sent to.
+    pub fn router(&self) -> &Router {
+        &self.router
+    }
+}
+
+// The bodies of the error responses are human-readable messages.
+fn message(response: &Response<Vec<u8>>) -> String {
+    String::from_utf8_lossy(response.body()).into_owned()
+}
@@ -0,0 +1,135 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// The token bucket limiting the rate of the invocations of a command, set with
+/// `#[command(rate_limit = "...")]`.
+///
+/// The bucket holds up to [`RateLimit::calls`] tokens and is refilled at the rate of [`RateLimit::calls`]
+/// tokens per [`RateLimit::period`]. Each invocation takes a token, so a burst of up to [`RateLimit::calls`]
+/// invocations is allowed after a pause.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimit {
+    calls: u32,
+    period: Duration,
+}
+
+impl RateLimit {
+    /// Creates the [`RateLimit`] allowing `calls` invocations per `period`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either `calls` or `period` is zero.
+    pub const fn new(calls: u32, period: Duration) -> Self {
+        assert!(calls > 0, "the rate limit must allow at least one call");
+        assert!(
+            !period.is_zero(),
+            "the period of the rate limit must be positive"
+        );
+        Self { calls, period }
+    }
+
+    /// Returns the number of the invocations allowed per [`RateLimit::period`], which is also the size of the burst.
+    pub const fn calls(&self) -> u32 {
+        self.calls
+    }
+
+    /// Returns the period the invocations are counted over.
+    pub const fn period(&self) -> Duration {
+        self.period
+    }
+}
+
+/// Why the invocation of a command was rejected.
+#[derive(Debug)]
+pub(crate) enum Throttled {
+    /// The command is already running the given number of invocations.
+    Concurrency(usize),
+    /// The rate limit is exhausted until the next token is refilled.
+    RateLimit { retry_after: Duration },
+}
+
+/// The limits of the invocations of a command, shared by all the requests to the same [`Router`](crate::Router).
+pub(crate) struct Throttle {
+    max_concurrency: Option<usize>,
+    running: AtomicUsize,
+    bucket: Option<Mutex<Bucket>>,
+}
+
+struct Bucket {
+    rate_limit: RateLimit,
+    // Fractional, so that the tokens are refilled continuously rather than once per period.
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+impl Bucket {
+    fn take(&mut self, now: Instant) -> Result<(), Duration> {
+        let calls: f64 = f64::from(self.rate_limit.calls);
+        let per_second: f64 = calls / self.rate_limit.period.as_secs_f64();
+        let elapsed: f64 = now
+            .saturating_duration_since(self.refilled_at)
+            .as_secs_f64();
+        self.tokens = (self.tokens + elapsed * per_second).min(calls);
+        self.refilled_at = now;
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - self.tokens) / per_second))
+        }
+    }
+}
+
+/// The slot of a running invocation, released when the invocation is answered.
+pub(crate) struct Permit(Arc<Throttle>);
+
+impl Drop for Permit {
+    fn drop(&mut self) {
+        self.0.running.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+impl Throttle {
+    /// Creates the [`Throttle`] with the given limits, or returns `None` if there are none.
+    pub(crate) fn new(
+        max_concurrency: Option<usize>,
+        rate_limit: Option<RateLimit>,
+    ) -> Option<Arc<Self>> {
+        if max_concurrency.is_none() && rate_limit.is_none() {
+            return None;
+        }
+        let bucket = rate_limit.map(|rate_limit| {
+            Mutex::new(Bucket {
+                rate_limit,
+                tokens: f64::from(rate_limit.calls),
+                refilled_at: Instant::now(),
+            })
+        });
+        Some(Arc::new(Self {
+            max_concurrency,
+            running: AtomicUsize::new(0),
+            bucket,
+        }))
+    }
+
+    /// Admits the invocation if it's within the limits. The rejected invocations don't take the tokens
+    /// of the rate limit.
+    pub(crate) fn acquire(self: &Arc<Self>) -> Result<Permit, Throttled> {
+        let running: usize = self.running.fetch_add(1, Ordering::AcqRel);
+        // The permit releases the slot even if the invocation is rejected below.
+        let permit = Permit(self.clone());
+        if let Some(max_concurrency) = self.max_concurrency {
+            if running >= max_concurrency {
+                return Err(Throttled::Concurrency(max_concurrency));
+            }
+        }
+        if let Some(bucket) = &self.bucket {
+            let mut bucket = bucket.lock().unwrap_or_else(|e| e.into_inner());
+            if let Err(retry_after) = bucket.take(Instant::now()) {
+                return Err(Throttled::RateLimit { retry_after });
+            }
+        }
+        Ok(permit)
+    }
+}
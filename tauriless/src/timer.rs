@@ -0,0 +1,156 @@
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{self, AtomicBool, AtomicU64};
+use std::sync::{Arc, Condvar, Mutex, OnceLock};
+use std::task::{Context, Poll, Waker};
+use std::time::{Duration, Instant};
+
+use crate::Sleep;
+
+/// Returns the future that is completed after the given duration, measured by the timer thread owned by `tauriless`.
+///
+/// Unlike the timers of the async runtimes, it works regardless of the runtime polling the future.
+pub(crate) fn sleep(duration: Duration) -> Sleep {
+    Box::pin(ThreadSleep {
+        deadline: Instant::now() + duration,
+        shared: Arc::new(Shared::default()),
+        key: None,
+    })
+}
+
+#[derive(Default)]
+struct Shared {
+    fired: AtomicBool,
+    waker: Mutex<Option<Waker>>,
+}
+
+impl Shared {
+    fn fire(&self) {
+        self.fired.store(true, atomic::Ordering::Release);
+        if let Some(waker) = self.waker.lock().unwrap().take() {
+            waker.wake();
+        }
+    }
+
+    fn is_fired(&self) -> bool {
+        self.fired.load(atomic::Ordering::Acquire)
+    }
+}
+
+// The entries are ordered by their deadlines, and the ids tell apart the ones with the same deadline.
+type Key = (Instant, u64);
+
+struct Timer {
+    entries: Mutex<BTreeMap<Key, Arc<Shared>>>,
+    condvar: Condvar,
+    next_id: AtomicU64,
+}
+
+impl Timer {
+    fn global() -> &'static Timer {
+        static GLOBAL: OnceLock<Timer> = OnceLock::new();
+        GLOBAL.get_or_init(|| {
+            std::thread::Builder::new()
+                .name("tauriless-timer".to_string())
+                .spawn(|| Timer::global().run())
+                .expect("Failed to spawn the thread of the `tauriless` timer.");
+            Timer {
+                entries: Mutex::new(BTreeMap::new()),
+                condvar: Condvar::new(),
+                next_id: AtomicU64::new(0),
+            }
+        })
+    }
+
+    fn register(&self, deadline: Instant, shared: Arc<Shared>) -> Key {
+        let key: Key = (
+            deadline,
+            self.next_id.fetch_add(1, atomic::Ordering::Relaxed),
+        );
+        self.entries.lock().unwrap().insert(key, shared);
+        // The new entry may have the earliest deadline.
+        self.condvar.notify_one();
+        key
+    }
+
+    // The thread isn't notified, since it merely wakes up for nothing if the entry was the earliest one.
+    fn cancel(&self, key: &Key) {
+        self.entries.lock().unwrap().remove(key);
+    }
+
+    fn run(&self) {
+        let mut entries = self.entries.lock().unwrap();
+        loop {
+            let now = Instant::now();
+            while entries
+                .first_key_value()
+                .is_some_and(|((deadline, _), _)| *deadline <= now)
+            {
+                entries.pop_first().unwrap().1.fire();
+            }
+            entries = match entries.first_key_value() {
+                Some(((deadline, _), _)) => {
+                    let timeout: Duration = *deadline - now;
+                    self.condvar.wait_timeout(entries, timeout).unwrap().0
+                }
+                None => self.condvar.wait(entries).unwrap(),
+            };
+        }
+    }
+}
+
+struct ThreadSleep {
+    deadline: Instant,
+    shared: Arc<Shared>,
+    // The key of the entry of the timer, once it's registered on the first poll.
+    key: Option<Key>,
+}
+
+impl Future for ThreadSleep {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if self.shared.is_fired() {
+            return Poll::Ready(());
+        }
+        *self.shared.waker.lock().unwrap() = Some(cx.waker().clone());
+        if self.key.is_none() {
+            self.key = Some(Timer::global().register(self.deadline, self.shared.clone()));
+        }
+        // The timer could have fired before the waker was stored.
+        if self.shared.is_fired() {
+            return Poll::Ready(());
+        }
+        Poll::Pending
+    }
+}
+
+// The sleep dropped before its deadline, e.g. when the command completed in time or was cancelled,
+// doesn't hold its entry and its waker until then.
+impl Drop for ThreadSleep {
+    fn drop(&mut self) {
+        if let Some(key) = &self.key {
+            if !self.shared.is_fired() {
+                Timer::global().cancel(key);
+            }
+        }
+    }
+}
+
+/// Awaits the future unless the sleep is completed first, in which case the future is dropped
+/// and `None` is returned. Without the sleep, the future is simply awaited.
+#[doc(hidden)]
+pub async fn timeout<F: Future>(sleep: Option<Sleep>, future: F) -> Option<F::Output> {
+    let Some(mut sleep) = sleep else {
+        return Some(future.await);
+    };
+    let mut future: Pin<Box<F>> = Box::pin(future);
+    std::future::poll_fn(move |cx| {
+        if let Poll::Ready(output) = future.as_mut().poll(cx) {
+            return Poll::Ready(Some(output));
+        }
+        sleep.as_mut().poll(cx).map(|()| None)
+    })
+    .await
+}
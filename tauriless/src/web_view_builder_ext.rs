@@ -1,4 +1,4 @@
-use crate::Commands;
+use crate::{Commands, Config, StateManager};
 
 /// An [extension trait] for [`wry::WebViewBuilder`]. Notably, implements
 /// [`with_tauriless_commands`](WebViewBuilderExt::with_tauriless_commands).
@@ -6,10 +6,45 @@ use crate::Commands;
 /// [extension trait]: https://rust-lang.github.io/rfcs/0445-extension-trait-conventions.html
 pub trait WebViewBuilderExt {
     fn with_tauriless_commands<C: Commands>(self, commands: C) -> Self;
+    /// Same as [`with_tauriless_commands`](WebViewBuilderExt::with_tauriless_commands) but also
+    /// makes the given state available to the commands taking [`State`](crate::State) arguments.
+    fn with_tauriless_commands_and_state<C: Commands>(
+        self,
+        commands: C,
+        state: StateManager,
+    ) -> Self;
+    /// Same as [`with_tauriless_commands`](WebViewBuilderExt::with_tauriless_commands) but uses
+    /// the given [`Config`], e.g. with an explicit [`Spawner`](crate::Spawner).
+    fn with_tauriless_commands_and_config<C: Commands>(self, commands: C, config: Config) -> Self;
+    /// Registers the custom protocol serving the assets embedded with
+    /// [`RustEmbed`](rust_embed::RustEmbed) at [`ASSETS_URL`](crate::assets::ASSETS_URL).
+    /// See the [`assets`](crate::assets) module.
+    #[cfg(feature = "assets")]
+    fn with_tauriless_assets<A: rust_embed::RustEmbed>(self) -> Self;
 }
 
 impl<'a> WebViewBuilderExt for wry::WebViewBuilder<'a> {
     fn with_tauriless_commands<C: Commands>(self, commands: C) -> Self {
-        commands.register_commands(self)
+        commands.register_commands(self, Config::new())
+    }
+
+    fn with_tauriless_commands_and_state<C: Commands>(
+        self,
+        commands: C,
+        state: StateManager,
+    ) -> Self {
+        commands.register_commands(self, Config::new().with_state(state))
+    }
+
+    fn with_tauriless_commands_and_config<C: Commands>(self, commands: C, config: Config) -> Self {
+        commands.register_commands(self, config)
+    }
+
+    #[cfg(feature = "assets")]
+    fn with_tauriless_assets<A: rust_embed::RustEmbed>(self) -> Self {
+        self.with_custom_protocol(
+            crate::assets::ASSETS_PROTOCOL.to_string(),
+            |request: wry::http::Request<Vec<u8>>| crate::assets::serve::<A>(&request),
+        )
     }
 }
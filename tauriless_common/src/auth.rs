@@ -0,0 +1,6 @@
+/// The name of the header carrying the per-launch authentication token of the requests invoking the commands.
+pub const AUTH_TOKEN_HEADER: &str = "x-tauriless-token";
+
+/// The name of the global object mapping the names of the custom protocols to their authentication tokens,
+/// which the core process defines in the WebView for `tauriless-js`.
+pub const AUTH_TOKENS_GLOBAL: &str = "__TAURILESS_AUTH_TOKENS__";
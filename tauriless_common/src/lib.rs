@@ -1,4 +1,8 @@
 #![doc = include_str!("../README.md")]
 
+/// The module for `tauriless` implementation details that concern the authentication of requests.
+pub mod auth;
+/// The module for `tauriless` implementation details that concern HTTP status codes.
+pub mod status;
 /// The module for `tauriless` implementation details that concern URLs.
 pub mod url;
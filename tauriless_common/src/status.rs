@@ -0,0 +1,17 @@
+/// The HTTP status code of the response for a command that returned [`Err`].
+///
+/// The body of such response is the serialized error value, just like the body of the
+/// response with [`200 OK`](https://developer.mozilla.org/en-US/docs/Web/HTTP/Status/200) status code is the
+/// serialized [`Ok`] value.
+pub const COMMAND_ERROR: u16 = 422;
+
+/// The HTTP status code of the response for a command whose invocation was cancelled by the frontend.
+///
+/// The frontend that cancelled the invocation doesn't wait for the response, so it's sent only to
+/// release the request.
+pub const COMMAND_CANCELLED: u16 = 499;
+
+/// The HTTP status code of the response for a command that didn't complete in time.
+///
+/// The body of such response is the UTF-8 message describing the timeout.
+pub const COMMAND_TIMEOUT: u16 = 504;
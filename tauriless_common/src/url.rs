@@ -6,10 +6,103 @@ pub const TAURILESS_PROTOCOL: &str = "tauiriless";
 ///
 /// [RFC 1738]: https://datatracker.ietf.org/doc/html/rfc1738
 pub fn command_to_url(cmd_name: &str) -> String {
-    // Q: Is this Windows-specific?
+    command_to_url_at(&default_base_url(), cmd_name)
+}
+
+/// The form of the URLs under which the WebView exposes the custom protocols.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum UrlScheme {
+    /// `http://<protocol>.localhost/`, as exposed by WebView2 on Windows.
+    Http,
+    /// `<protocol>://localhost/`, as exposed by WebKitGTK on Linux and WKWebView on macOS.
+    Custom,
+}
+
+impl UrlScheme {
+    /// Returns the form of the URLs used by the WebView on the target platform of the compilation.
+    ///
+    /// The WASM module of `tauriless-js` doesn't know the platform at compile time, so it detects
+    /// the form at runtime instead.
+    pub const fn native() -> Self {
+        if cfg!(windows) {
+            Self::Http
+        } else {
+            Self::Custom
+        }
+    }
+
+    /// Returns the base URL of the given custom protocol, without the trailing slash.
+    pub fn base_url(self, protocol: &str) -> String {
+        match self {
+            Self::Http => format!("http://{protocol}.localhost"),
+            Self::Custom => format!("{protocol}://localhost"),
+        }
+    }
+}
+
+/// Returns the base URL of the custom protocol used by `tauriless` on the target platform of the compilation,
+/// without the trailing slash.
+pub fn default_base_url() -> String {
+    native_base_url(TAURILESS_PROTOCOL)
+}
+
+/// Returns the base URL of the given custom protocol on the target platform of the compilation,
+/// without the trailing slash.
+pub fn native_base_url(protocol: &str) -> String {
+    UrlScheme::native().base_url(protocol)
+}
+
+/// The name of the global object mapping the names of the custom protocols to their base URLs, which the core
+/// process defines in the WebView for `tauriless-js`.
+pub const BASE_URLS_GLOBAL: &str = "__TAURILESS_BASE_URLS__";
+
+/// Same as [`command_to_url`] but the URL starts with the given base URL, e.g. `http://127.0.0.1:3001`
+/// of the development server, instead of the [default one](default_base_url).
+pub fn command_to_url_at(base_url: &str, cmd_name: &str) -> String {
     format!(
-        "http://{proto}.localhost/{command}",
-        proto = TAURILESS_PROTOCOL,
+        "{base_url}/{command}",
+        base_url = base_url.trim_end_matches('/'),
         command = cmd_name.replace('_', "-")
     )
 }
+
+/// The name of the query parameter carrying the id of the invocation of a command. The invocations with an id
+/// can be cancelled via [`cancellation_url`].
+pub const INVOCATION_ID_PARAM: &str = "invocation-id";
+
+/// The path of the URL used to cancel the invocation of a command. Unlike the paths of the commands,
+/// it contains underscores, so it can't clash with any of them.
+pub const CANCELLATION_PATH: &str = "__cancel__";
+
+/// Same as [`command_to_url`] but the URL also carries the id of the invocation, which allows to cancel it.
+pub fn command_to_url_with_invocation_id(cmd_name: &str, invocation_id: &str) -> String {
+    command_to_url_with_invocation_id_at(&default_base_url(), cmd_name, invocation_id)
+}
+
+/// Same as [`command_to_url_with_invocation_id`] but the URL starts with the given base URL.
+pub fn command_to_url_with_invocation_id_at(
+    base_url: &str,
+    cmd_name: &str,
+    invocation_id: &str,
+) -> String {
+    format!(
+        "{url}?{param}={invocation_id}",
+        url = command_to_url_at(base_url, cmd_name),
+        param = INVOCATION_ID_PARAM,
+    )
+}
+
+/// Returns the URL for cancelling the invocation with the given id.
+pub fn cancellation_url(invocation_id: &str) -> String {
+    cancellation_url_at(&default_base_url(), invocation_id)
+}
+
+/// Same as [`cancellation_url`] but the URL starts with the given base URL.
+pub fn cancellation_url_at(base_url: &str, invocation_id: &str) -> String {
+    format!(
+        "{base_url}/{path}?{param}={invocation_id}",
+        base_url = base_url.trim_end_matches('/'),
+        path = CANCELLATION_PATH,
+        param = INVOCATION_ID_PARAM,
+    )
+}
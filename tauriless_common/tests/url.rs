@@ -0,0 +1,38 @@
+use tauriless_common::url::{
+    cancellation_url_at, command_to_url, command_to_url_at, default_base_url, native_base_url,
+    UrlScheme, TAURILESS_PROTOCOL,
+};
+
+#[test]
+fn base_urls_depend_on_the_scheme() {
+    assert_eq!(UrlScheme::Http.base_url("proto"), "http://proto.localhost");
+    assert_eq!(UrlScheme::Custom.base_url("proto"), "proto://localhost");
+}
+
+#[test]
+fn native_scheme_matches_the_webview() {
+    let expected = if cfg!(windows) {
+        UrlScheme::Http
+    } else {
+        UrlScheme::Custom
+    };
+    assert_eq!(UrlScheme::native(), expected);
+    assert_eq!(default_base_url(), expected.base_url(TAURILESS_PROTOCOL));
+    assert_eq!(native_base_url("my-app"), expected.base_url("my-app"));
+}
+
+#[test]
+fn command_urls_are_relative_to_the_base_url() {
+    assert_eq!(
+        command_to_url("do_stuff"),
+        format!("{}/do-stuff", default_base_url())
+    );
+    assert_eq!(
+        command_to_url_at("http://127.0.0.1:3001/", "do_stuff"),
+        "http://127.0.0.1:3001/do-stuff"
+    );
+    assert_eq!(
+        cancellation_url_at("proto://localhost", "42"),
+        "proto://localhost/__cancel__?invocation-id=42"
+    );
+}
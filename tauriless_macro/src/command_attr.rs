@@ -0,0 +1,150 @@
+use crate::duration::{parse_duration_millis, parse_rate_limit};
+
+/// The naming convention for the keys of the named arguments of a command.
+#[derive(Clone, Copy, Default)]
+pub(crate) enum RenameAll {
+    /// The keys are the names of the parameters as written.
+    #[default]
+    SnakeCase,
+    /// The keys are the names of the parameters converted to `camelCase`, as is common in JavaScript.
+    CamelCase,
+}
+
+impl RenameAll {
+    pub(crate) fn apply(self, name: &str) -> String {
+        match self {
+            RenameAll::SnakeCase => name.to_string(),
+            RenameAll::CamelCase => {
+                let mut parts = name.split('_').filter(|part| !part.is_empty());
+                let mut renamed: String = parts.next().unwrap_or_default().to_string();
+                for part in parts {
+                    let mut chars = part.chars();
+                    if let Some(first) = chars.next() {
+                        renamed.extend(first.to_uppercase());
+                        renamed.push_str(chars.as_str());
+                    }
+                }
+                renamed
+            }
+        }
+    }
+}
+
+/// The arguments of the `#[command(...)]` attribute.
+#[derive(Default)]
+pub(crate) struct CommandAttr {
+    /// `rename_all = "camelCase"` or `rename_all = "snake_case"`.
+    pub(crate) rename_all: RenameAll,
+    /// `blocking`.
+    pub(crate) blocking: bool,
+    /// `local`.
+    pub(crate) local: bool,
+    /// `timeout = "5s"`, in milliseconds.
+    pub(crate) timeout_millis: Option<u64>,
+    /// `max_concurrency = 4`.
+    pub(crate) max_concurrency: Option<usize>,
+    /// `rate_limit = "10/s"`, as the number of the calls per the period in milliseconds.
+    pub(crate) rate_limit: Option<(u32, u64)>,
+}
+
+impl CommandAttr {
+    pub(crate) fn parse_meta(&mut self, meta: syn::meta::ParseNestedMeta) -> syn::Result<()> {
+        if meta.path.is_ident("rename_all") {
+            let value: syn::LitStr = meta.value()?.parse()?;
+            self.rename_all = match value.value().as_str() {
+                "snake_case" => RenameAll::SnakeCase,
+                "camelCase" => RenameAll::CamelCase,
+                _ => {
+                    return Err(syn::Error::new(
+                        value.span(),
+                        "expected \"snake_case\" or \"camelCase\"",
+                    ))
+                }
+            };
+            Ok(())
+        } else if meta.path.is_ident("blocking") {
+            self.blocking = true;
+            Ok(())
+        } else if meta.path.is_ident("timeout") {
+            let value: syn::LitStr = meta.value()?.parse()?;
+            match parse_duration_millis(&value.value()) {
+                Some(millis) => {
+                    self.timeout_millis = Some(millis);
+                    Ok(())
+                }
+                None => Err(syn::Error::new(
+                    value.span(),
+                    "expected a duration like \"500ms\", \"5s\", \"2m\" or \"1h\"",
+                )),
+            }
+        } else if meta.path.is_ident("max_concurrency") {
+            let value: syn::LitInt = meta.value()?.parse()?;
+            match value.base10_parse::<usize>()? {
+                0 => Err(syn::Error::new(
+                    value.span(),
+                    "expected a positive number of invocations",
+                )),
+                max_concurrency => {
+                    self.max_concurrency = Some(max_concurrency);
+                    Ok(())
+                }
+            }
+        } else if meta.path.is_ident("rate_limit") {
+            let value: syn::LitStr = meta.value()?.parse()?;
+            match parse_rate_limit(&value.value()) {
+                Some(rate_limit) => {
+                    self.rate_limit = Some(rate_limit);
+                    Ok(())
+                }
+                None => Err(syn::Error::new(
+                    value.span(),
+                    "expected a rate like \"10/s\", \"100/m\" or \"5/10s\"",
+                )),
+            }
+        } else if meta.path.is_ident("local") {
+            self.local = true;
+            Ok(())
+        } else {
+            Err(meta.error("unsupported `command` attribute argument"))
+        }
+    }
+}
+
+/// The arguments of the `#[tauriless(...)]` attributes of a parameter of the command.
+#[derive(Default)]
+pub(crate) struct ParamAttr {
+    /// `#[tauriless(default)]` makes the argument omittable, in which case it's filled with
+    /// [`Default::default()`].
+    pub(crate) default: bool,
+}
+
+impl ParamAttr {
+    /// Parses the `#[tauriless(...)]` attributes of the parameter and removes them
+    /// because they're not known to the compiler.
+    pub(crate) fn take_from(attrs: &mut Vec<syn::Attribute>) -> syn::Result<Self> {
+        let mut param_attr = ParamAttr::default();
+        let mut result: syn::Result<()> = Ok(());
+        attrs.retain(|attr| {
+            if !attr.path().is_ident("tauriless") {
+                return true;
+            }
+            if let Err(e) = attr.parse_nested_meta(|meta| param_attr.parse_meta(meta)) {
+                match &mut result {
+                    Ok(()) => result = Err(e),
+                    Err(errors) => errors.combine(e),
+                }
+            }
+            false
+        });
+        result.map(|()| param_attr)
+    }
+
+    fn parse_meta(&mut self, meta: syn::meta::ParseNestedMeta) -> syn::Result<()> {
+        if meta.path.is_ident("default") {
+            self.default = true;
+            Ok(())
+        } else {
+            Err(meta.error("unsupported `tauriless` attribute argument"))
+        }
+    }
+}
@@ -0,0 +1,29 @@
+// Parses the durations like "500ms", "5s", "2m" or "1h" into milliseconds.
+//
+// Unlike `humantime`, only a single integer with a unit is supported, which is enough for timeouts.
+pub(crate) fn parse_duration_millis(s: &str) -> Option<u64> {
+    let digits_end: usize = s.find(|c: char| !c.is_ascii_digit())?;
+    let (amount, unit) = s.split_at(digits_end);
+    let amount: u64 = amount.parse().ok()?;
+    let millis_per_unit: u64 = match unit {
+        "ms" => 1,
+        "s" => 1_000,
+        "m" => 60 * 1_000,
+        "h" => 60 * 60 * 1_000,
+        _ => return None,
+    };
+    amount.checked_mul(millis_per_unit)
+}
+
+// Parses the rates like "10/s", "100/m" or "5/10s" into the number of the calls and the period in milliseconds.
+// The amount of the period can be omitted if it's one.
+pub(crate) fn parse_rate_limit(s: &str) -> Option<(u32, u64)> {
+    let (calls, period) = s.split_once('/')?;
+    let calls: u32 = calls.parse().ok().filter(|calls| *calls > 0)?;
+    let period_millis: u64 = if period.starts_with(|c: char| c.is_ascii_digit()) {
+        parse_duration_millis(period)?
+    } else {
+        parse_duration_millis(&format!("1{period}"))?
+    };
+    (period_millis > 0).then_some((calls, period_millis))
+}
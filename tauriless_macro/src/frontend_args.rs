@@ -0,0 +1,49 @@
+/// The argument of the command supplied by the frontend.
+pub(crate) struct FrontendArg<'a> {
+    /// The identifier of the argument in the generated code, e.g. `arg0`.
+    pub(crate) ident: syn::Ident,
+    pub(crate) ty: &'a syn::Type,
+    /// The key of the argument when the arguments are supplied by parameter name.
+    pub(crate) key: String,
+    /// Whether the argument can be omitted by the frontend, in which case it's filled
+    /// with [`Default::default()`].
+    pub(crate) omittable: bool,
+}
+
+/// Returns the name of the parameter if its pattern is a plain identifier, e.g. `x` in `fn f(x: i32)`.
+///
+/// The parameters with other patterns, e.g. `WrapperType(x)` in `fn f(WrapperType(x): WrapperType<i32>)`,
+/// are keyed by their position instead.
+pub(crate) fn param_name(pat: &syn::Pat) -> Option<String> {
+    match pat {
+        syn::Pat::Ident(pat_ident) => {
+            let name = pat_ident.ident.to_string();
+            Some(name.trim_start_matches("r#").to_string())
+        }
+        _ => None,
+    }
+}
+
+// Unfortunately, the types are compared syntactically, so type aliases are not recognized.
+pub(crate) fn is_option(ty: &syn::Type) -> bool {
+    let syn::Type::Path(type_path) = ty else {
+        return false;
+    };
+    if type_path.qself.is_some() {
+        return false;
+    }
+    let Some(last_segment) = type_path.path.segments.last() else {
+        return false;
+    };
+    last_segment.ident == "Option"
+        && matches!(last_segment.arguments, syn::PathArguments::AngleBracketed(_))
+}
+
+/// Returns the minimal number of the positional arguments, i.e. the number of the arguments
+/// up to and including the last one that can't be omitted.
+pub(crate) fn min_arity(frontend_args: &[FrontendArg]) -> usize {
+    frontend_args
+        .iter()
+        .rposition(|arg| !arg.omittable)
+        .map_or(0, |i| i + 1)
+}
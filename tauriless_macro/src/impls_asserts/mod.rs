@@ -15,6 +15,8 @@ use impls_exprs::{
 use equityped_arg_pats::EquitypedArgPats;
 use dyn_clone::DynClone;
 
+use crate::special_args::special_arg;
+
 // Raison d'être: https://github.com/rust-lang/rust/issues/32220
 pub(super) trait CloneableIterator<T>: Iterator<Item = T> + DynClone {}
 impl<T, It> CloneableIterator<T> for It
@@ -112,6 +114,10 @@ pub(crate) fn extend_with_serde_deserialize_impls_asserts<'a,'b>(
             .chain(args_iter)
             .into_iter()
         {
+            // The arguments like `State<T>` are not supplied by the frontend.
+            if special_arg(&typed_arg.ty).is_some() {
+                continue;
+            }
             let ty: Box<syn::Type> = typed_arg.ty;
             let pat: Box<syn::Pat> = typed_arg.pat;
             let ty_str = ty.to_token_stream().to_string();
@@ -1,19 +1,31 @@
 #![doc = include_str!("../README.md")]
 
 use dyn_clone::clone_box;
-use proc_macro::{token_stream::IntoIter as TokenTreeIter, TokenStream};
+use proc_macro::TokenStream;
 use quote::quote;
 use syn::punctuated::Punctuated;
 use syn::token::Comma;
 use syn::{FnArg, ItemFn, ReturnType};
-use tauriless_common::url::TAURILESS_PROTOCOL;
 
+mod command_attr;
+mod duration;
+mod frontend_args;
 mod impls_asserts;
+mod named_args;
+mod positional_args;
+mod return_type;
+mod special_args;
 
+use command_attr::{CommandAttr, ParamAttr};
+use frontend_args::{is_option, min_arity, param_name, FrontendArg};
 use impls_asserts::{
     extend_with_serde_deserialize_impls_asserts, extend_with_serde_serialize_impls_assert,
     CloneableIterator,
 };
+use named_args::extend_with_named_args;
+use positional_args::extend_with_positional_args;
+use return_type::result_ok_err;
+use special_args::{special_arg, SpecialArg};
 
 struct Commands(syn::punctuated::Punctuated<syn::Ident, syn::token::Comma>);
 impl syn::parse::Parse for Commands {
@@ -26,6 +38,8 @@ impl syn::parse::Parse for Commands {
 
 fn extend_with_command(
     ts: &mut proc_macro2::TokenStream,
+    command_attr: &CommandAttr,
+    param_attrs: &[ParamAttr],
     fn_item: &ItemFn,
     fn_typed_args: &dyn CloneableIterator<&syn::PatType>,
 ) {
@@ -35,58 +49,233 @@ fn extend_with_command(
     let asyncness = &fn_item.sig.asyncness;
     let cmd_name = format!("__command_{name}");
     let cmd_name = syn::Ident::new(&cmd_name, name.span());
-    let typed_args_count = clone_box(fn_typed_args).count();
-    let types_iter = clone_box(fn_typed_args).map(|pat_type| &pat_type.ty);
-    let args_iter =
-        (0..typed_args_count).map(|i| syn::Ident::new(&format!("arg{}", i), name.span()));
-    let args_iter_clone1 = args_iter.clone();
-    let args_iter_clone2 = args_iter.clone();
+
+    let named_args_name = format!("__command_{name}_named_args");
+    let named_args_name = syn::Ident::new(&named_args_name, name.span());
+    let positional_args_name = format!("__command_{name}_positional_args");
+    let positional_args_name = syn::Ident::new(&positional_args_name, name.span());
+
+    // The arguments supplied by the frontend.
+    let mut frontend_args = Vec::<FrontendArg>::new();
+    // The statements that fill the arguments which are not supplied by the frontend.
+    let mut special_args_stmts = proc_macro2::TokenStream::new();
+    // The identifiers of all arguments, in the order of the parameters of the command-function.
+    let mut call_args = Vec::<syn::Ident>::new();
+    let mut observes_cancellation: bool = false;
+    for ((i, pat_type), param_attr) in clone_box(fn_typed_args).enumerate().zip(param_attrs) {
+        let arg = syn::Ident::new(&format!("arg{}", i), name.span());
+        let ty: &syn::Type = &pat_type.ty;
+        match special_arg(ty) {
+            Some(SpecialArg::State) => special_args_stmts.extend(quote! {
+                let #arg: #ty = state.get();
+            }),
+            Some(SpecialArg::CancellationToken) => {
+                observes_cancellation = true;
+                special_args_stmts.extend(quote! {
+                    let #arg: #ty = cancellation_token.clone();
+                })
+            }
+            None => {
+                let key = match param_name(&pat_type.pat) {
+                    Some(param_name) => command_attr.rename_all.apply(&param_name),
+                    None => frontend_args.len().to_string(),
+                };
+                frontend_args.push(FrontendArg {
+                    ident: arg.clone(),
+                    ty,
+                    key,
+                    omittable: param_attr.default || is_option(ty),
+                });
+            }
+        };
+        call_args.push(arg);
+    }
+    let types: Vec<&syn::Type> = frontend_args.iter().map(|arg| arg.ty).collect();
+    let args: Vec<&syn::Ident> = frontend_args.iter().map(|arg| &arg.ident).collect();
+    let arg_names: Vec<&str> = frontend_args.iter().map(|arg| arg.key.as_str()).collect();
+    let arity: usize = frontend_args.len();
+    let min_arity: usize = min_arity(&frontend_args);
+    extend_with_positional_args(ts, &positional_args_name, &name_str, &frontend_args);
+    extend_with_named_args(ts, &named_args_name, &name_str, &frontend_args);
+
+    let is_blocking: bool = command_attr.blocking;
+    let is_local: bool = command_attr.local;
+    let timeout = match command_attr.timeout_millis {
+        Some(millis) => quote! { core::option::Option::Some(core::time::Duration::from_millis(#millis)) },
+        None => quote! { core::option::Option::None },
+    };
+    let max_concurrency = match command_attr.max_concurrency {
+        Some(max_concurrency) => quote! { core::option::Option::Some(#max_concurrency) },
+        None => quote! { core::option::Option::None },
+    };
+    let rate_limit = match command_attr.rate_limit {
+        Some((calls, period_millis)) => quote! {
+            core::option::Option::Some(tauriless::RateLimit::new(#calls, core::time::Duration::from_millis(#period_millis)))
+        },
+        None => quote! { core::option::Option::None },
+    };
     let return_type = match &fn_item.sig.output {
         ReturnType::Default => quote! {()},
         ReturnType::Type(_right_arrow, ty) => quote! {#ty},
     };
+    let args_items = if args.is_empty() {
+        quote! {
+            type PositionalArgs = #positional_args_name;
+            type NamedArgs = #named_args_name;
+
+            fn from_positional_args(_positional_args: Self::PositionalArgs) -> Self::Args {}
+
+            fn from_named_args(_named_args: Self::NamedArgs) -> Self::Args {}
+        }
+    } else {
+        quote! {
+            type PositionalArgs = #positional_args_name;
+            type NamedArgs = #named_args_name;
+
+            fn from_positional_args(positional_args: Self::PositionalArgs) -> Self::Args {
+                (#(positional_args.#args,)*)
+            }
+
+            fn from_named_args(named_args: Self::NamedArgs) -> Self::Args {
+                (#(named_args.#args,)*)
+            }
+        }
+    };
+    let result_items = match result_ok_err(&fn_item.sig.output) {
+        Some((ok, err)) => quote! {
+            type Ok = #ok;
+            type Err = #err;
+
+            fn into_result(ret: Self::RetTy) -> core::result::Result<Self::Ok, Self::Err> {
+                ret
+            }
+        },
+        None => quote! {
+            type Ok = Self::RetTy;
+            type Err = tauriless::NoError;
+
+            fn into_result(ret: Self::RetTy) -> core::result::Result<Self::Ok, Self::Err> {
+                core::result::Result::Ok(ret)
+            }
+        },
+    };
+    // Only one of `async_command` and `local_async_command` is used, depending on `Command::IS_LOCAL`.
+    // The future of the `local` command is not required to be `Send`.
+    let command_body = quote! {
+        #special_args_stmts
+        async move {
+            #name(#(#call_args),*).await
+        }
+    };
+    let (async_command_body, local_async_command_body) = if is_local {
+        let message = format!(
+            "the `{name_str}` command is local, so `Command::local_async_command` is called instead"
+        );
+        (quote! { async move { unreachable!(#message) } }, command_body)
+    } else {
+        let message = format!(
+            "the `{name_str}` command is not local, so `Command::async_command` is called instead"
+        );
+        (command_body, quote! { async move { unreachable!(#message) } })
+    };
+    // The synchronous commands are only called with `Command::sync_command`, and the asynchronous ones never are.
+    let sync_only_message = format!(
+        "the `{name_str}` command is synchronous, so `Command::sync_command` is called instead"
+    );
+    let async_only_message = format!(
+        "the `{name_str}` command is asynchronous, so `Command::sync_command` is never called"
+    );
+    // The dispatch functions have the bounds that only their kind of commands satisfies,
+    // e.g. the arguments of the `local` commands don't have to be `Send`.
+    let dispatch = match (asyncness.is_some(), is_local, is_blocking) {
+        (false, _, false) => quote! { tauriless::dispatch_sync },
+        (false, _, true) => quote! { tauriless::dispatch_blocking },
+        (true, false, _) => quote! { tauriless::dispatch_async },
+        (true, true, _) => quote! { tauriless::dispatch_local },
+    };
+    let handle_item = quote! {
+        fn handle(config: &std::sync::Arc<tauriless::Config>, uri: &tauriless::http::Uri, body: std::vec::Vec<u8>, responder: tauriless::Responder) {
+            #dispatch::<Self>(config, uri, body, responder)
+        }
+    };
     let trait_impl = if asyncness.is_none() {
         quote! {
             impl tauriless::Command for #cmd_name {
-                type Args = (#(#types_iter),*);
+                type Args = (#(#types,)*);
                 type RetTy = #return_type;
                 const NAME: &'static str = #name_str;
                 #[doc = "URLs can't contain underscores, so they are replaced with dashes"]
                 const URL_NAME: &'static str = #url_name;
+                const ARITY: usize = #arity;
+                const ARG_NAMES: &'static [&'static str] = &[#(#arg_names),*];
+                const MIN_ARITY: usize = #min_arity;
                 const IS_ASYNC: bool = false;
+                const IS_BLOCKING: bool = #is_blocking;
+                const IS_LOCAL: bool = false;
+                const OBSERVES_CANCELLATION: bool = #observes_cancellation;
+                const TIMEOUT: core::option::Option<core::time::Duration> = core::option::Option::None;
+                const MAX_CONCURRENCY: core::option::Option<usize> = #max_concurrency;
+                const RATE_LIMIT: core::option::Option<tauriless::RateLimit> = #rate_limit;
+                #args_items
+                #result_items
 
-                fn sync_command( (#(#args_iter),*): Self::Args ) -> Self::RetTy {
-                    #name(#(#args_iter_clone1),*)
+                #[allow(unused_variables)]
+                fn sync_command( (#(#args,)*): Self::Args, state: &tauriless::StateManager, cancellation_token: &tauriless::CancellationToken ) -> Self::RetTy {
+                    #special_args_stmts
+                    #name(#(#call_args),*)
                 }
 
                 #[allow(unused_variables)]
-                fn async_command( (#(#args_iter_clone2),*): Self::Args ) -> impl std::future::Future<Output = Self::RetTy> {
-                    async move { unimplemented!() }
+                fn async_command( (#(#args,)*): Self::Args, state: &tauriless::StateManager, cancellation_token: &tauriless::CancellationToken ) -> impl std::future::Future<Output = Self::RetTy> {
+                    async move { unreachable!(#sync_only_message) }
                 }
+
+                #[allow(unused_variables)]
+                fn local_async_command( (#(#args,)*): Self::Args, state: &tauriless::StateManager, cancellation_token: &tauriless::CancellationToken ) -> impl std::future::Future<Output = Self::RetTy> {
+                    async move { unreachable!(#sync_only_message) }
+                }
+
+                #handle_item
             }
         }
     } else {
         quote! {
             impl tauriless::Command for #cmd_name {
-                type Args = (#(#types_iter),*);
+                type Args = (#(#types,)*);
                 #[doc = "The return type of the command, modulo the Future wrapper."]
                 type RetTy = #return_type;
                 const NAME: &'static str = #name_str;
                 #[doc = "URLs can't contain underscores, so they are replaced with dashes"]
                 const URL_NAME: &'static str = #url_name;
+                const ARITY: usize = #arity;
+                const ARG_NAMES: &'static [&'static str] = &[#(#arg_names),*];
+                const MIN_ARITY: usize = #min_arity;
                 const IS_ASYNC: bool = true;
+                const IS_BLOCKING: bool = false;
+                const IS_LOCAL: bool = #is_local;
+                const OBSERVES_CANCELLATION: bool = #observes_cancellation;
+                const TIMEOUT: core::option::Option<core::time::Duration> = #timeout;
+                const MAX_CONCURRENCY: core::option::Option<usize> = #max_concurrency;
+                const RATE_LIMIT: core::option::Option<tauriless::RateLimit> = #rate_limit;
+                #args_items
+                #result_items
 
                 #[allow(unused_variables)]
-                fn sync_command( (#(#args_iter),*): Self::Args ) -> Self::RetTy {
-                    todo!()
+                fn sync_command( (#(#args,)*): Self::Args, state: &tauriless::StateManager, cancellation_token: &tauriless::CancellationToken ) -> Self::RetTy {
+                    unreachable!(#async_only_message)
                 }
 
+                #[allow(unused_variables)]
+                fn async_command( (#(#args,)*): Self::Args, state: &tauriless::StateManager, cancellation_token: &tauriless::CancellationToken ) -> impl std::future::Future<Output = Self::RetTy> {
+                    #async_command_body
+                }
 
-                fn async_command( (#(#args_iter_clone2),*): Self::Args ) -> impl std::future::Future<Output = Self::RetTy> {
-                    async move {
-                        #name(#(#args_iter_clone1),*).await
-                    }
+                #[allow(unused_variables)]
+                fn local_async_command( (#(#args,)*): Self::Args, state: &tauriless::StateManager, cancellation_token: &tauriless::CancellationToken ) -> impl std::future::Future<Output = Self::RetTy> {
+                    #local_async_command_body
                 }
+
+                #handle_item
             }
         }
     };
@@ -102,8 +291,42 @@ fn extend_with_command(
 
 /// The `tauriless` equivalent of [`#[tauri::command]`](https://tauri.app/v1/guides/features/command/).
 ///
-/// **Note that running asynchronous commands requires global tokio runtime.
-/// See [`tokio::runtime::Runtime::enter`](https://docs.rs/tokio/latest/tokio/runtime/struct.Runtime.html#method.enter).**
+/// **Note that running asynchronous and blocking commands requires a `tauriless::Spawner`, e.g.
+/// `tauriless::TokioSpawner`, passed with `tauriless::Config::with_spawner`.**
+///
+/// ## Arguments
+///
+/// * `rename_all = "camelCase"` makes the command expect `camelCase` keys when the arguments are supplied
+///   by parameter name, e.g. `invoke("cmd", { firstArg: 1 })` for `fn cmd(first_arg: i32)`.
+///
+/// * `blocking` makes the synchronous command run on the blocking thread pool of the `tauriless::Spawner`
+///   (e.g. [`tokio::task::spawn_blocking`](https://docs.rs/tokio/latest/tokio/task/fn.spawn_blocking.html))
+///   rather than on the thread of the event loop, so that a slow command, e.g. the one hashing a file,
+///   doesn't freeze the window.
+///
+/// * `local` makes the asynchronous command run on the single-threaded executor, so that its future
+///   doesn't have to be `Send`, e.g. when it holds an `Rc` or a `RefCell` across an `.await`. The arguments
+///   of such command don't have to be `Send` either.
+///
+/// * `timeout = "5s"` makes the asynchronous command time out after the given duration, e.g. `"500ms"`, `"5s"`,
+///   `"2m"` or `"1h"`. The future of the command that didn't complete in time is dropped and the promise returned by
+///   `invoke` from `tauriless-js` is rejected with a `TimeoutError`. It overrides the default timeout set with
+///   `tauriless::Config::with_default_timeout`.
+///
+/// * `max_concurrency = 4` limits the number of the invocations of the command running at once. The excess
+///   invocations are rejected with `429 Too Many Requests` rather than queued, so that a runaway frontend
+///   can't starve the runtime.
+///
+/// * `rate_limit = "10/s"` limits the rate of the invocations of the command with a token bucket, e.g. `"100/m"`
+///   or `"5/10s"`. Up to the given number of invocations can be made at once, and the bucket is refilled
+///   continuously over the period. The excess invocations are rejected with `429 Too Many Requests` and
+///   a `Retry-After` header.
+///
+/// The parameters of type `tauriless::State<T>` and `tauriless::CancellationToken` are not supplied by the frontend.
+/// Instead, they're filled with the managed state and the token cancelled when the frontend aborts the invocation.
+///
+/// The parameters of the command can be marked with `#[tauriless(default)]` to make them omittable, just like
+/// the parameters of type `Option<T>`. The omitted arguments are filled with `Default::default()`.
 ///
 /// ## Example
 ///
@@ -113,7 +336,7 @@ fn extend_with_command(
 ///     event_loop::{ControlFlow, EventLoop},
 ///     window::WindowBuilder,
 /// };
-/// use tauriless::{command, commands, WebViewBuilderExt};
+/// use tauriless::{command, commands, Config, TokioSpawner, WebViewBuilderExt};
 /// use wry::WebViewBuilder;
 ///
 /// #[command]
@@ -130,10 +353,6 @@ fn extend_with_command(
 ///        .enable_all()
 ///        .build()
 ///        .unwrap();
-///    // This allows us to use tokio::spawn inside wry asynchronous custom protocol handlers.
-///    // Since wry doesn't allow us to pass a runtime to the WebViewBuilder, we have to use a global runtime.
-///    let _rt_guard = rt.enter();
-///
 ///    let event_loop = EventLoop::new();
 ///    let window = WindowBuilder::new()
 ///        .with_title("My Tauriless App")
@@ -144,7 +363,11 @@ fn extend_with_command(
 ///    
 ///    let _webview = WebViewBuilder::new(&window)
 ///        // ...
-///        .with_tauriless_commands(commands!(argsless_sync_command, async_command_with_args))
+///        .with_tauriless_commands_and_config(
+///            commands!(argsless_sync_command, async_command_with_args),
+///            // The async commands are spawned on the given runtime.
+///            Config::new().with_spawner(TokioSpawner::new(rt.handle().clone())),
+///        )
 ///        .build()?;
 ///
 ///    event_loop.run(move |event, _, control_flow| {
@@ -163,17 +386,46 @@ fn extend_with_command(
 /// ```
 #[proc_macro_attribute]
 pub fn command(attr: TokenStream, item: TokenStream) -> TokenStream {
-    {
-        let mut tt_iter: TokenTreeIter = attr.into_iter();
-        assert!(
-            tt_iter.next().is_none(),
-            "The {fn_name} attribute does not take any arguments",
-            fn_name = stringify!(command)
-        );
-    }
+    let mut command_attr = CommandAttr::default();
+    let command_attr_parser = syn::meta::parser(|meta| command_attr.parse_meta(meta));
+    syn::parse_macro_input!(attr with command_attr_parser);
 
-    let fn_item = syn::parse_macro_input!(item as ItemFn);
+    let mut fn_item = syn::parse_macro_input!(item as ItemFn);
+    let mut param_attrs = Vec::<ParamAttr>::new();
+    for fn_arg in fn_item.sig.inputs.iter_mut() {
+        if let FnArg::Typed(pat_type) = fn_arg {
+            match ParamAttr::take_from(&mut pat_type.attrs) {
+                Ok(param_attr) => param_attrs.push(param_attr),
+                Err(e) => return e.to_compile_error().into(),
+            }
+        }
+    }
+    let fn_item = fn_item;
     let fn_sig: &syn::Signature = &fn_item.sig;
+    if command_attr.blocking && fn_sig.asyncness.is_some() {
+        return syn::Error::new_spanned(
+            fn_sig.asyncness,
+            "`blocking` is only supported for synchronous commands",
+        )
+        .to_compile_error()
+        .into();
+    }
+    if command_attr.timeout_millis.is_some() && fn_sig.asyncness.is_none() {
+        return syn::Error::new_spanned(
+            fn_sig.fn_token,
+            "`timeout` is only supported for asynchronous commands",
+        )
+        .to_compile_error()
+        .into();
+    }
+    if command_attr.local && fn_sig.asyncness.is_none() {
+        return syn::Error::new_spanned(
+            fn_sig.fn_token,
+            "`local` is only supported for asynchronous commands",
+        )
+        .to_compile_error()
+        .into();
+    }
 
     let inputs: &Punctuated<FnArg, Comma> = &fn_sig.inputs;
     let return_type: &ReturnType = &fn_sig.output;
@@ -183,15 +435,22 @@ pub fn command(attr: TokenStream, item: TokenStream) -> TokenStream {
         extend_with_serde_deserialize_impls_asserts(&mut ts, inputs);
     let fn_typed_args: &dyn CloneableIterator<&syn::PatType> = &*fn_typed_args;
     extend_with_serde_serialize_impls_assert(&mut ts, return_type);
-    extend_with_command(&mut ts, &fn_item, fn_typed_args);
+    extend_with_command(
+        &mut ts,
+        &command_attr,
+        &param_attrs,
+        &fn_item,
+        fn_typed_args,
+    );
     ts.extend(quote!(#fn_item));
     ts.into()
 }
 
-/// Creates an object that can be accepted by `tauriless::WebViewBuilderExt::with_tauriless_commands`.
+/// Creates the `tauriless::CommandRegistry` with the given commands, which can be accepted by
+/// `tauriless::WebViewBuilderExt::with_tauriless_commands`.
 ///
-/// **Note that running asynchronous commands requires global tokio runtime.
-/// See [`tokio::runtime::Runtime::enter`](https://docs.rs/tokio/latest/tokio/runtime/struct.Runtime.html#method.enter).**
+/// **Note that running asynchronous and blocking commands requires a `tauriless::Spawner`, e.g.
+/// `tauriless::TokioSpawner`, passed with `tauriless::Config::with_spawner`.**
 ///
 /// ## Example
 ///
@@ -201,7 +460,7 @@ pub fn command(attr: TokenStream, item: TokenStream) -> TokenStream {
 ///     event_loop::{ControlFlow, EventLoop},
 ///     window::WindowBuilder,
 /// };
-/// use tauriless::{command, commands, WebViewBuilderExt};
+/// use tauriless::{command, commands, Config, TokioSpawner, WebViewBuilderExt};
 /// use wry::WebViewBuilder;
 ///
 /// #[command]
@@ -218,10 +477,6 @@ pub fn command(attr: TokenStream, item: TokenStream) -> TokenStream {
 ///        .enable_all()
 ///        .build()
 ///        .unwrap();
-///    // This allows us to use tokio::spawn inside wry asynchronous custom protocol handlers.
-///    // Since wry doesn't allow us to pass a runtime to the WebViewBuilder, we have to use a global runtime.
-///    let _rt_guard = rt.enter();
-///
 ///    let event_loop = EventLoop::new();
 ///    let window = WindowBuilder::new()
 ///        .with_title("My Tauriless App")
@@ -232,7 +487,11 @@ pub fn command(attr: TokenStream, item: TokenStream) -> TokenStream {
 ///    
 ///    let _webview = WebViewBuilder::new(&window)
 ///        // ...
-///        .with_tauriless_commands(commands!(argsless_sync_command, async_command_with_args))
+///        .with_tauriless_commands_and_config(
+///            commands!(argsless_sync_command, async_command_with_args),
+///            // The async commands are spawned on the given runtime.
+///            Config::new().with_spawner(TokioSpawner::new(rt.handle().clone())),
+///        )
 ///        .build()?;
 ///
 ///    event_loop.run(move |event, _, control_flow| {
@@ -257,80 +516,12 @@ pub fn commands(input: TokenStream) -> TokenStream {
         let cmd_name = format!("__command_{ident_str}");
         syn::Ident::new(&cmd_name, ident.span())
     });
-    let command_struct_idents_clone = command_struct_idents.clone();
-
-    let mut async_proto_branches = proc_macro2::TokenStream::new();
-
-    for cmd in command_struct_idents_clone {
-        async_proto_branches.extend(quote! {
-            <#cmd as tauriless::Command>::URL_NAME => {
-                let args: <#cmd as tauriless::Command>::Args = match tauriless::slice_to_deserialize(body.as_slice()) {
-                    Ok(args) => args,
-                    Err(e) => return responder.respond(tauriless::handle_deserialization_error(<#cmd as tauriless::Command>::NAME, e)),
-                };
-                if <#cmd as tauriless::Command>::IS_ASYNC {
-                    let handle = tokio::runtime::Handle::try_current().expect("Using async protocol handler requires entering the tokio runtime context prior to that. Use `let _rt_guard = rt.enter()` to enter the runtime context. See <https://docs.rs/tokio/latest/tokio/runtime/struct.Runtime.html#method.enter>.");
-                    handle.spawn(async move {
-                        let ret: <#cmd as tauriless::Command>::RetTy = <#cmd as tauriless::Command>::async_command(args).await;
-                        match tauriless::serialize_to_vec_u8(&ret) {
-                            Ok(ret) => {
-                                responder.respond(wry::http::response::Response::builder()
-                                    .status(wry::http::StatusCode::OK)
-                                    .header(
-                                        wry::http::header::ACCESS_CONTROL_ALLOW_ORIGIN,
-                                        wry::http::HeaderValue::from_static("*"),
-                                    )
-                                    .body(std::borrow::Cow::Owned(ret))
-                                    .unwrap()
-                                );
-                            },
-                            Err(e) => {
-                                responder.respond(tauriless::handle_serialization_error(e));
-                            }
-                        }
-                    });
-                } else {
-                    let ret: <#cmd as tauriless::Command>::RetTy = <#cmd as tauriless::Command>::sync_command(args);
-                    match tauriless::serialize_to_vec_u8(&ret) {
-                        Ok(ret) => {
-                            responder.respond(wry::http::response::Response::builder()
-                                .status(wry::http::StatusCode::OK)
-                                .header(
-                                    wry::http::header::ACCESS_CONTROL_ALLOW_ORIGIN,
-                                    wry::http::HeaderValue::from_static("*"),
-                                )
-                                .body(std::borrow::Cow::Owned(ret))
-                                .unwrap()
-                            );
-                        },
-                        Err(e) => {
-                            responder.respond(tauriless::handle_serialization_error(e));
-                        }
-                    }
-                }
-            },
-        });
-    }
-
-    async_proto_branches.extend(quote! {
-        _ => responder.respond(tauriless::handle_unknown_command(path)),
-    });
 
     let ts = quote! {
         {
-            // Using closures caused an error.
-            fn commands<'a>(builder: wry::WebViewBuilder<'a>) -> wry::WebViewBuilder<'a> {
-                builder.with_asynchronous_custom_protocol( #TAURILESS_PROTOCOL.to_string(), | req: wry::http::request::Request<Vec<u8>>, responder: wry::RequestAsyncResponder | {
-                    let (parts, body): (wry::http::request::Parts, Vec<u8>) = req.into_parts();
-                    let uri: wry::http::uri::Uri = parts.uri;
-                    let path: &str = uri.path();
-                    let path: &str = path.trim_start_matches('/');
-                    match path {
-                        #async_proto_branches
-                    };
-                })
-            }
-            commands
+            let mut registry = tauriless::CommandRegistry::new();
+            #(registry.register::<#command_struct_idents>();)*
+            registry
         }
     };
 
@@ -0,0 +1,85 @@
+use quote::quote;
+
+use crate::frontend_args::FrontendArg;
+
+// Extends the token stream with the struct holding the arguments supplied by parameter name and with
+// its `serde::Deserialize` implementation.
+//
+// The implementation is written by hand rather than derived so that the users of `tauriless` don't have to
+// enable the `derive` feature of `serde`.
+pub(crate) fn extend_with_named_args(
+    ts: &mut proc_macro2::TokenStream,
+    named_args_name: &syn::Ident,
+    cmd_name_str: &str,
+    frontend_args: &[FrontendArg],
+) {
+    let idents: Vec<&syn::Ident> = frontend_args.iter().map(|arg| &arg.ident).collect();
+    let types: Vec<&syn::Type> = frontend_args.iter().map(|arg| arg.ty).collect();
+    let keys: Vec<&str> = frontend_args.iter().map(|arg| arg.key.as_str()).collect();
+    let missing_values = frontend_args.iter().map(|arg| {
+        let key: &str = arg.key.as_str();
+        if arg.omittable {
+            quote! { core::default::Default::default() }
+        } else {
+            quote! { return core::result::Result::Err(<M::Error as serde::de::Error>::missing_field(#key)) }
+        }
+    });
+    let expecting = format!("the named arguments of the `{cmd_name_str}` command");
+
+    ts.extend(quote! {
+        #[doc(hidden)]
+        #[allow(non_camel_case_types)]
+        struct #named_args_name {
+            #(#idents: #types,)*
+        }
+
+        impl<'de> serde::Deserialize<'de> for #named_args_name {
+            fn deserialize<D>(deserializer: D) -> core::result::Result<Self, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct Visitor;
+
+                impl<'de> serde::de::Visitor<'de> for Visitor {
+                    type Value = #named_args_name;
+
+                    fn expecting(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+                        formatter.write_str(#expecting)
+                    }
+
+                    #[allow(unused_mut)]
+                    fn visit_map<M>(self, mut map: M) -> core::result::Result<Self::Value, M::Error>
+                    where
+                        M: serde::de::MapAccess<'de>,
+                    {
+                        #(let mut #idents: core::option::Option<#types> = core::option::Option::None;)*
+                        while let core::option::Option::Some(key) = map.next_key::<std::string::String>()? {
+                            match key.as_str() {
+                                #(
+                                    #keys => {
+                                        if #idents.is_some() {
+                                            return core::result::Result::Err(<M::Error as serde::de::Error>::duplicate_field(#keys));
+                                        }
+                                        #idents = core::option::Option::Some(map.next_value()?);
+                                    }
+                                )*
+                                _ => {
+                                    map.next_value::<serde::de::IgnoredAny>()?;
+                                }
+                            }
+                        }
+                        #(
+                            let #idents: #types = match #idents {
+                                core::option::Option::Some(value) => value,
+                                core::option::Option::None => #missing_values,
+                            };
+                        )*
+                        core::result::Result::Ok(#named_args_name { #(#idents),* })
+                    }
+                }
+
+                deserializer.deserialize_map(Visitor)
+            }
+        }
+    });
+}
@@ -0,0 +1,94 @@
+use quote::quote;
+
+use crate::frontend_args::{min_arity, FrontendArg};
+
+// Extends the token stream with the struct holding the arguments supplied positionally and with
+// its `serde::Deserialize` implementation.
+//
+// Unlike the tuple deserialization, this one fills the omitted trailing arguments with their default values.
+// The length of the sequence is checked by `tauriless_serde::slice_to_deserialize_args()` beforehand.
+pub(crate) fn extend_with_positional_args(
+    ts: &mut proc_macro2::TokenStream,
+    positional_args_name: &syn::Ident,
+    cmd_name_str: &str,
+    frontend_args: &[FrontendArg],
+) {
+    let idents: Vec<&syn::Ident> = frontend_args.iter().map(|arg| &arg.ident).collect();
+    let types: Vec<&syn::Type> = frontend_args.iter().map(|arg| arg.ty).collect();
+    let missing_values = frontend_args.iter().enumerate().map(|(i, arg)| {
+        if arg.omittable {
+            quote! { core::default::Default::default() }
+        } else {
+            quote! { return core::result::Result::Err(<A::Error as serde::de::Error>::invalid_length(#i, &self)) }
+        }
+    });
+    let expecting = format!("the positional arguments of the `{cmd_name_str}` command");
+    // The commands whose arguments can all be omitted can be invoked without arguments at all.
+    let visit_unit = if min_arity(frontend_args) == 0 {
+        quote! {
+            fn visit_unit<E>(self) -> core::result::Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                core::result::Result::Ok(#positional_args_name {
+                    #(#idents: core::default::Default::default(),)*
+                })
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    ts.extend(quote! {
+        #[doc(hidden)]
+        #[allow(non_camel_case_types)]
+        struct #positional_args_name {
+            #(#idents: #types,)*
+        }
+
+        impl<'de> serde::Deserialize<'de> for #positional_args_name {
+            fn deserialize<D>(deserializer: D) -> core::result::Result<Self, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct Visitor;
+
+                impl<'de> serde::de::Visitor<'de> for Visitor {
+                    type Value = #positional_args_name;
+
+                    fn expecting(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+                        formatter.write_str(#expecting)
+                    }
+
+                    #[allow(unused_mut, unused_variables, unused_assignments)]
+                    fn visit_seq<A>(self, mut seq: A) -> core::result::Result<Self::Value, A::Error>
+                    where
+                        A: serde::de::SeqAccess<'de>,
+                    {
+                        // Once the sequence is exhausted, it's not polled anymore.
+                        let mut exhausted: bool = false;
+                        #(
+                            let next: core::option::Option<#types> = if exhausted {
+                                core::option::Option::None
+                            } else {
+                                seq.next_element::<#types>()?
+                            };
+                            let #idents: #types = match next {
+                                core::option::Option::Some(value) => value,
+                                core::option::Option::None => {
+                                    exhausted = true;
+                                    #missing_values
+                                }
+                            };
+                        )*
+                        core::result::Result::Ok(#positional_args_name { #(#idents),* })
+                    }
+
+                    #visit_unit
+                }
+
+                deserializer.deserialize_seq(Visitor)
+            }
+        }
+    });
+}
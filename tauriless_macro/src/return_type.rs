@@ -0,0 +1,33 @@
+/// Returns `T` and `E` if the return type is `Result<T, E>` or a path ending with `Result<T, E>`,
+/// e.g. `std::result::Result<T, E>`.
+///
+/// The commands returning such types respond with the `E` value as an error, so that the
+/// `tauriless-js` `invoke` promise is rejected with it.
+// Unfortunately, the types are compared syntactically, so single-parameter aliases like
+// `std::io::Result<T>` are not recognized.
+pub(crate) fn result_ok_err(return_type: &syn::ReturnType) -> Option<(&syn::Type, &syn::Type)> {
+    let syn::ReturnType::Type(_right_arrow, ty) = return_type else {
+        return None;
+    };
+    let syn::Type::Path(type_path) = &**ty else {
+        return None;
+    };
+    if type_path.qself.is_some() {
+        return None;
+    }
+    let last_segment: &syn::PathSegment = type_path.path.segments.last()?;
+    if last_segment.ident != "Result" {
+        return None;
+    }
+    let syn::PathArguments::AngleBracketed(generic_args) = &last_segment.arguments else {
+        return None;
+    };
+    let mut type_args = generic_args.args.iter().filter_map(|arg| match arg {
+        syn::GenericArgument::Type(ty) => Some(ty),
+        _ => None,
+    });
+    match (type_args.next(), type_args.next(), type_args.next()) {
+        (Some(ok), Some(err), None) => Some((ok, err)),
+        _ => None,
+    }
+}
@@ -0,0 +1,30 @@
+/// The kinds of command arguments that are not supplied by the frontend.
+///
+/// The arguments of these kinds are not included in `Command::Args` and are filled by `tauriless` instead.
+pub(crate) enum SpecialArg {
+    /// `State<T>` or `tauriless::State<T>`, filled from the `tauriless::StateManager`.
+    State,
+    /// `CancellationToken` or `tauriless::CancellationToken`, cancelled when the frontend aborts the invocation.
+    CancellationToken,
+}
+
+// Unfortunately, the types are compared syntactically, so type aliases and re-exports under
+// different names are not recognized.
+pub(crate) fn special_arg(ty: &syn::Type) -> Option<SpecialArg> {
+    let syn::Type::Path(type_path) = ty else {
+        return None;
+    };
+    if type_path.qself.is_some() {
+        return None;
+    }
+    let last_segment: &syn::PathSegment = type_path.path.segments.last()?;
+    match last_segment.ident.to_string().as_str() {
+        "State" if matches!(last_segment.arguments, syn::PathArguments::AngleBracketed(_)) => {
+            Some(SpecialArg::State)
+        }
+        "CancellationToken" if matches!(last_segment.arguments, syn::PathArguments::None) => {
+            Some(SpecialArg::CancellationToken)
+        }
+        _ => None,
+    }
+}
@@ -0,0 +1,74 @@
+use tauriless::Command;
+use tauriless_macro::command;
+
+#[command]
+fn zero() -> i32 {
+    0
+}
+
+#[command]
+fn one(v: Vec<i32>) -> usize {
+    v.len()
+}
+
+#[command]
+fn two(a: i32, b: i32) -> i32 {
+    a + b
+}
+
+fn encode(value: serde_json::Value) -> Vec<u8> {
+    pot::to_vec(&value).unwrap()
+}
+
+fn arity_error<C: Command>(value: serde_json::Value) -> String {
+    match tauriless::deserialize_args::<C>(&encode(value)) {
+        Ok(_) => panic!("expected an arity error"),
+        Err(e) => e.to_string(),
+    }
+}
+
+#[test]
+fn args_are_always_tuples() {
+    let _: <__command_zero as Command>::Args = ();
+    let _: <__command_one as Command>::Args = (vec![1, 2],);
+    let _: <__command_two as Command>::Args = (1, 2);
+    assert_eq!(<__command_zero as Command>::ARITY, 0);
+    assert_eq!(<__command_one as Command>::ARITY, 1);
+    assert_eq!(<__command_two as Command>::ARITY, 2);
+}
+
+#[test]
+fn positional_args_are_always_arrays() {
+    tauriless::deserialize_args::<__command_zero>(&encode(serde_json::json!([]))).unwrap();
+    tauriless::deserialize_args::<__command_zero>(&encode(serde_json::Value::Null)).unwrap();
+
+    // A one-argument command whose argument is itself a sequence is not ambiguous.
+    let args =
+        tauriless::deserialize_args::<__command_one>(&encode(serde_json::json!([[1, 2, 3]])))
+            .unwrap();
+    assert_eq!(args, (vec![1, 2, 3],));
+
+    let args =
+        tauriless::deserialize_args::<__command_two>(&encode(serde_json::json!([1, 2]))).unwrap();
+    assert_eq!(args, (1, 2));
+}
+
+#[test]
+fn arity_mismatches_are_reported() {
+    assert_eq!(
+        arity_error::<__command_zero>(serde_json::json!([1])),
+        "expected 0 arguments, got 1"
+    );
+    assert_eq!(
+        arity_error::<__command_one>(serde_json::json!([1, 2, 3])),
+        "expected 1 argument, got 3"
+    );
+    assert_eq!(
+        arity_error::<__command_two>(serde_json::json!([1])),
+        "expected 2 arguments, got 1"
+    );
+    assert_eq!(
+        arity_error::<__command_two>(serde_json::json!(1)),
+        "expected an array of 2 arguments or an object of named arguments"
+    );
+}
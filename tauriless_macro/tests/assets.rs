@@ -0,0 +1,81 @@
+use std::borrow::Cow;
+
+use rust_embed::RustEmbed;
+use tauriless::assets::serve;
+use tauriless::http::{header, Request, Response};
+
+#[derive(RustEmbed)]
+#[folder = "tests/fixtures/assets/"]
+struct Assets;
+
+fn get(path: &str, headers: &[(&str, &str)]) -> Response<Cow<'static, [u8]>> {
+    let mut request = Request::get(format!("http://tauriless-assets.localhost{path}"));
+    for (name, value) in headers {
+        request = request.header(*name, *value);
+    }
+    serve::<Assets>(&request.body(Vec::new()).unwrap())
+}
+
+fn header<'a>(response: &'a Response<Cow<'static, [u8]>>, name: header::HeaderName) -> &'a str {
+    response.headers()[name].to_str().unwrap()
+}
+
+#[test]
+fn assets_are_served_with_mime_types() {
+    let response = get("/app.js", &[]);
+    assert_eq!(response.status(), 200);
+    assert!(header(&response, header::CONTENT_TYPE).contains("javascript"));
+    assert_eq!(
+        response.body().as_ref(),
+        Assets::get("app.js").unwrap().data.as_ref()
+    );
+
+    let response = get("/style.css", &[]);
+    assert_eq!(header(&response, header::CONTENT_TYPE), "text/css");
+
+    let response = get("/missing.png", &[]);
+    assert_eq!(response.status(), 404);
+}
+
+#[test]
+fn index_is_served_for_routes() {
+    let index = Assets::get("index.html").unwrap().data;
+    for path in ["/", "/settings", "/users/42"] {
+        let response = get(path, &[]);
+        assert_eq!(response.status(), 200);
+        assert_eq!(header(&response, header::CONTENT_TYPE), "text/html");
+        assert_eq!(response.body().as_ref(), index.as_ref());
+    }
+}
+
+#[test]
+fn unmodified_assets_are_not_sent() {
+    let response = get("/app.js", &[]);
+    let etag = header(&response, header::ETAG).to_string();
+
+    let response = get("/app.js", &[("if-none-match", &etag)]);
+    assert_eq!(response.status(), 304);
+    assert!(response.body().is_empty());
+
+    let response = get("/app.js", &[("if-none-match", "\"outdated\"")]);
+    assert_eq!(response.status(), 200);
+}
+
+#[test]
+fn precompressed_variants_are_served_when_accepted() {
+    let response = get("/app.js", &[("accept-encoding", "gzip, deflate")]);
+    assert_eq!(response.status(), 200);
+    assert_eq!(header(&response, header::CONTENT_ENCODING), "gzip");
+    assert!(header(&response, header::CONTENT_TYPE).contains("javascript"));
+    assert_eq!(
+        response.body().as_ref(),
+        Assets::get("app.js.gz").unwrap().data.as_ref()
+    );
+
+    let response = get("/app.js", &[("accept-encoding", "gzip;q=0")]);
+    assert!(response.headers().get(header::CONTENT_ENCODING).is_none());
+
+    // There is no precompressed variant of the stylesheet.
+    let response = get("/style.css", &[("accept-encoding", "gzip")]);
+    assert!(response.headers().get(header::CONTENT_ENCODING).is_none());
+}
@@ -0,0 +1,67 @@
+use std::sync::Mutex;
+use std::thread::ThreadId;
+
+use tauriless::http::Request;
+use tauriless::{Command, Config, Router, TokioSpawner};
+use tauriless_common::url::command_to_url;
+use tauriless_macro::{command, commands};
+
+// The threads the commands were run on, keyed by the name of the command.
+static THREADS: Mutex<Vec<(&str, ThreadId)>> = Mutex::new(Vec::new());
+
+fn record_thread(command: &'static str) {
+    THREADS
+        .lock()
+        .unwrap()
+        .push((command, std::thread::current().id()));
+}
+
+fn thread_of(command: &str) -> ThreadId {
+    THREADS
+        .lock()
+        .unwrap()
+        .iter()
+        .find(|(name, _)| *name == command)
+        .map(|(_, thread)| *thread)
+        .unwrap()
+}
+
+#[command(blocking)]
+fn blocking_sum(numbers: Vec<u64>) -> u64 {
+    record_thread("blocking_sum");
+    numbers.iter().sum()
+}
+
+#[command]
+fn sync_sum(numbers: Vec<u64>) -> u64 {
+    record_thread("sync_sum");
+    numbers.iter().sum()
+}
+
+const _: () = assert!(<__command_blocking_sum as Command>::IS_BLOCKING);
+const _: () = assert!(!<__command_sync_sum as Command>::IS_BLOCKING);
+
+#[test]
+fn blocking_commands_run_off_the_dispatching_thread() {
+    let rt = tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(1)
+        .build()
+        .unwrap();
+    let config = Config::new().with_spawner(TokioSpawner::new(rt.handle().clone()));
+    let router = Router::new(commands!(blocking_sum, sync_sum), config);
+    let invoke = |command: &str| {
+        let request = Request::post(command_to_url(command))
+            .body(tauriless::serialize_to_vec_u8(&(vec![1u64, 2, 3],)).unwrap())
+            .unwrap();
+        let response = rt.block_on(router.handle(request));
+        assert_eq!(response.status(), 200);
+        tauriless::slice_to_deserialize::<u64>(response.body()).unwrap()
+    };
+
+    // The thread calling the `Router` stands in for the thread of the event loop.
+    assert_eq!(invoke("blocking_sum"), 6);
+    assert_ne!(thread_of("blocking_sum"), std::thread::current().id());
+
+    assert_eq!(invoke("sync_sum"), 6);
+    assert_eq!(thread_of("sync_sum"), std::thread::current().id());
+}
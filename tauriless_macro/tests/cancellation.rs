@@ -0,0 +1,118 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use tauriless::{CancellationToken, Command, StateManager};
+use tauriless_macro::command;
+
+#[command]
+async fn never_returns() -> i32 {
+    std::future::pending::<()>().await;
+    0
+}
+
+#[command]
+async fn returns_on_cancellation(
+    token: CancellationToken,
+    cleaned_up: tauriless::State<Arc<AtomicBool>>,
+) -> i32 {
+    token.cancelled().await;
+    cleaned_up.store(true, Ordering::Relaxed);
+    -1
+}
+
+#[command(blocking)]
+fn is_cancelled(token: CancellationToken) -> bool {
+    token.is_cancelled()
+}
+
+const _: () = assert!(!<__command_never_returns as Command>::OBSERVES_CANCELLATION);
+const _: () = assert!(<__command_returns_on_cancellation as Command>::OBSERVES_CANCELLATION);
+
+#[test]
+fn cancellation_token_is_not_part_of_args() {
+    let _: <__command_returns_on_cancellation as Command>::Args = ();
+    let _: <__command_is_cancelled as Command>::Args = ();
+}
+
+#[test]
+fn futures_of_commands_without_token_are_dropped() {
+    let rt = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+    let state = StateManager::new();
+    let token = CancellationToken::new();
+
+    let cancel = {
+        let token = token.clone();
+        async move { token.cancel() }
+    };
+    let ret = rt.block_on(async {
+        let command = tauriless::cancellable::<__command_never_returns, _>(
+            &token,
+            <__command_never_returns as Command>::async_command((), &state, &token),
+        );
+        let (ret, ()) = futures_join(command, cancel).await;
+        ret
+    });
+    assert_eq!(ret, None);
+}
+
+#[test]
+fn commands_with_token_observe_cancellation() {
+    let rt = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+    let cleaned_up = Arc::new(AtomicBool::new(false));
+    let state = StateManager::new().with(cleaned_up.clone());
+    let token = CancellationToken::new();
+
+    let cancel = {
+        let token = token.clone();
+        async move { token.cancel() }
+    };
+    let ret = rt.block_on(async {
+        let command = tauriless::cancellable::<__command_returns_on_cancellation, _>(
+            &token,
+            <__command_returns_on_cancellation as Command>::async_command((), &state, &token),
+        );
+        let (ret, ()) = futures_join(command, cancel).await;
+        ret
+    });
+    assert_eq!(ret, Some(-1));
+    assert!(cleaned_up.load(Ordering::Relaxed));
+
+    assert!(<__command_is_cancelled as Command>::sync_command(
+        (),
+        &state,
+        &token
+    ));
+}
+
+// Polls both futures concurrently, starting with the first one.
+async fn futures_join<A: std::future::Future, B: std::future::Future>(
+    a: A,
+    b: B,
+) -> (A::Output, B::Output) {
+    let mut a = Box::pin(a);
+    let mut b = Box::pin(b);
+    let mut a_out = None;
+    let mut b_out = None;
+    std::future::poll_fn(move |cx| {
+        if a_out.is_none() {
+            if let std::task::Poll::Ready(out) = a.as_mut().poll(cx) {
+                a_out = Some(out);
+            }
+        }
+        if b_out.is_none() {
+            if let std::task::Poll::Ready(out) = b.as_mut().poll(cx) {
+                b_out = Some(out);
+            }
+        }
+        if a_out.is_some() && b_out.is_some() {
+            std::task::Poll::Ready((a_out.take().unwrap(), b_out.take().unwrap()))
+        } else {
+            std::task::Poll::Pending
+        }
+    })
+    .await
+}
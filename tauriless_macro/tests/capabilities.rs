@@ -0,0 +1,235 @@
+use tauriless::http::{header, Request, Response};
+use tauriless::{
+    Capabilities, CapabilitiesError, Capability, CommandRegistry, Config, Plugin, Router, Scope,
+};
+use tauriless_common::url::command_to_url;
+use tauriless_macro::{command, commands};
+
+const VIEWER_URL: &str = "http://tauriless-assets.localhost/viewer/index.html";
+const ADMIN_URL: &str = "http://tauriless-assets.localhost/admin/index.html";
+
+#[command]
+fn get_document(id: u32) -> String {
+    format!("document {id}")
+}
+
+#[command]
+fn delete_document(_id: u32) {}
+
+#[command(rename_all = "camelCase")]
+fn read_file(file_path: String, _tags: Vec<String>) -> String {
+    file_path
+}
+
+#[command]
+fn list() -> Vec<String> {
+    Vec::new()
+}
+
+struct FsPlugin;
+
+impl Plugin for FsPlugin {
+    fn name(&self) -> &'static str {
+        "fs"
+    }
+
+    fn commands(&self) -> CommandRegistry {
+        commands!(list)
+    }
+}
+
+fn rt() -> tokio::runtime::Runtime {
+    tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap()
+}
+
+fn router(capabilities: Capabilities) -> Router {
+    let mut commands = commands!(get_document, delete_document, read_file);
+    commands.plugin(FsPlugin);
+    commands.register_fn("echo", |(s,): (String,)| s);
+    Router::new(commands, Config::new().with_capabilities(capabilities))
+}
+
+fn invoke<T: serde::Serialize>(
+    router: &Router,
+    page: Option<&str>,
+    command: &str,
+    args: &T,
+) -> Response<Vec<u8>> {
+    let mut request = Request::post(command_to_url(command));
+    if let Some(page) = page {
+        request = request.header(header::REFERER, page);
+    }
+    let request = request
+        .body(tauriless::serialize_to_vec_u8(args).unwrap())
+        .unwrap();
+    rt().block_on(router.handle(request))
+}
+
+#[test]
+fn only_granted_commands_are_invoked() {
+    let router = router(
+        Capabilities::new().with(
+            Capability::new("viewer")
+                .with_command("get_document")
+                .with_command("plugin:fs|*"),
+        ),
+    );
+    assert_eq!(invoke(&router, None, "get_document", &(1,)).status(), 200);
+    assert_eq!(invoke(&router, None, "plugin:fs|list", &()).status(), 200);
+    let response = invoke(&router, None, "delete_document", &(1,));
+    assert_eq!(response.status(), 403);
+    assert!(String::from_utf8_lossy(response.body()).contains("delete-document"));
+}
+
+#[test]
+fn capabilities_are_granted_per_url() {
+    let router = router(
+        Capabilities::new()
+            .with(
+                Capability::new("viewer")
+                    .with_url("http://tauriless-assets.localhost/viewer/*")
+                    .with_command("get_document"),
+            )
+            .with(
+                Capability::new("admin")
+                    .with_url("http://tauriless-assets.localhost/admin/*")
+                    .with_command("*"),
+            ),
+    );
+    assert_eq!(
+        invoke(&router, Some(VIEWER_URL), "get_document", &(1,)).status(),
+        200
+    );
+    assert_eq!(
+        invoke(&router, Some(VIEWER_URL), "delete_document", &(1,)).status(),
+        403
+    );
+    assert_eq!(
+        invoke(&router, Some(ADMIN_URL), "delete_document", &(1,)).status(),
+        200
+    );
+    let third_party = "https://example.com/";
+    assert_eq!(
+        invoke(&router, Some(third_party), "get_document", &(1,)).status(),
+        403
+    );
+    assert_eq!(invoke(&router, None, "get_document", &(1,)).status(), 403);
+}
+
+#[test]
+fn arguments_are_checked_against_scopes() {
+    let scope = Scope::new()
+        .with_allowed("docs/*")
+        .with_denied("docs/private/*");
+    let router = router(
+        Capabilities::new().with(
+            Capability::new("reader")
+                .with_command("read_file")
+                .with_command("echo")
+                .with_scope("read_file", "filePath", scope)
+                .with_scope("read_file", "tags", Scope::new().with_allowed("public"))
+                .with_scope("echo", "0", Scope::new().with_allowed("hello*")),
+        ),
+    );
+    let tags: Vec<&str> = vec!["public"];
+
+    // The arguments supplied positionally are looked up by the names of the parameters.
+    assert_eq!(
+        invoke(&router, None, "read_file", &("docs/a.txt", &tags)).status(),
+        200
+    );
+    let named = serde_json::json!({ "filePath": "docs/a.txt", "tags": tags });
+    assert_eq!(invoke(&router, None, "read_file", &named).status(), 200);
+
+    for args in [
+        serde_json::json!(["secrets.txt", ["public"]]),
+        serde_json::json!(["docs/private/a.txt", ["public"]]),
+        serde_json::json!(["docs/a.txt", ["public", "internal"]]),
+        serde_json::json!({ "filePath": "secrets.txt", "tags": [] }),
+    ] {
+        assert_eq!(invoke(&router, None, "read_file", &args).status(), 403);
+    }
+
+    // The arguments of the closures are looked up by position.
+    assert_eq!(
+        invoke(&router, None, "echo", &("hello, world",)).status(),
+        200
+    );
+    assert_eq!(invoke(&router, None, "echo", &("goodbye",)).status(), 403);
+}
+
+#[test]
+fn path_traversal_escapes_no_scope() {
+    let scope = Scope::new()
+        .with_allowed("docs/*")
+        .with_denied("docs/private/*");
+    let router = router(
+        Capabilities::new().with(
+            Capability::new("reader")
+                .with_command("read_file")
+                .with_scope("read_file", "filePath", scope),
+        ),
+    );
+    let tags: Vec<&str> = Vec::new();
+    assert_eq!(
+        invoke(&router, None, "read_file", &("docs/a/b.txt", &tags)).status(),
+        200
+    );
+    for path in [
+        "docs/../secret",
+        "docs/../../etc/passwd",
+        "docs/x/../private/key",
+        "docs\\..\\secret",
+        "docs/..",
+        "docs//private/key",
+        "docs/./private/key",
+        "docs\\private\\key",
+    ] {
+        let status = invoke(&router, None, "read_file", &(path, &tags)).status();
+        assert_eq!(status, 403, "{path}");
+    }
+}
+
+#[test]
+fn capabilities_are_loaded_from_json_and_toml() {
+    let json = r#"{
+        "capabilities": [
+            {
+                "name": "viewer",
+                "commands": ["get_document"],
+                "scopes": { "get_document": { "id": { "allow": ["1", "2"] } } }
+            }
+        ]
+    }"#;
+    let toml = r#"
+        [[capabilities]]
+        name = "viewer"
+        commands = ["get_document"]
+
+        [capabilities.scopes.get_document]
+        id = { allow = ["1", "2"] }
+    "#;
+    for capabilities in [
+        Capabilities::from_json_str(json).unwrap(),
+        Capabilities::from_toml_str(toml).unwrap(),
+    ] {
+        let router = router(capabilities);
+        assert_eq!(invoke(&router, None, "get_document", &(2,)).status(), 200);
+        assert_eq!(invoke(&router, None, "get_document", &(3,)).status(), 403);
+        assert_eq!(
+            invoke(&router, None, "delete_document", &(1,)).status(),
+            403
+        );
+    }
+
+    assert!(matches!(
+        Capabilities::from_json_str(r#"{ "capabilities": [{ "name": "x", "command": [] }] }"#),
+        Err(CapabilitiesError::Json(_))
+    ));
+    assert!(matches!(
+        Capabilities::from_file("capabilities.yaml"),
+        Err(CapabilitiesError::Io(_) | CapabilitiesError::UnsupportedFormat(_))
+    ));
+}
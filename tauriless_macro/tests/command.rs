@@ -21,8 +21,24 @@ fn command_with_args_and_return_type(a: i32, b: i32) -> i32 {
 // #[command]
 // fn command_with_non_deserializable_arg(b: i32, _a: NotDeserializable) {}
 
-// TODO: consider adding tests for de-serialization of arguments from JSON, and the serialization
-// of the return type.
+#[test]
+fn args_and_return_type_are_encoded_as_by_frontend() {
+    let rt = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+    let client = tauriless::testing::TestClient::new(
+        tauriless_macro::commands!(command_with_args_and_return_type),
+        tauriless::Config::new(),
+    );
+    let args: serde_json::Value = serde_json::json!([1, 2]);
+    let ret = rt
+        .block_on(client.invoke("command_with_args_and_return_type", args))
+        .unwrap();
+    assert_eq!(
+        ret,
+        serde_json::json!(command_with_args_and_return_type(1, 2))
+    );
+}
 
 fn main() {
     // args as written by the WASM library user
@@ -41,8 +57,11 @@ fn main() {
     // internal details of the custom protocol handler
     let args: <__command_command_with_args_and_return_type as tauriless::Command>::Args =
         pot::from_slice(&args).unwrap();
-    let ret =
-        <__command_command_with_args_and_return_type as tauriless::Command>::sync_command(args);
+    let ret = <__command_command_with_args_and_return_type as tauriless::Command>::sync_command(
+        args,
+        &tauriless::StateManager::new(),
+        &tauriless::CancellationToken::new(),
+    );
 
     let expected = command_with_args_and_return_type(1, 2);
     assert_eq!(ret, expected);
@@ -0,0 +1,172 @@
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use tauriless::dev_server::{DevServer, VITE_DEV_ORIGIN};
+use tauriless::{Config, Limits, TokioSpawner};
+use tauriless_macro::{command, commands};
+
+#[command]
+fn add(a: i32, b: i32) -> i32 {
+    a + b
+}
+
+#[command]
+async fn negate(n: i32) -> i32 {
+    -n
+}
+
+static RECORDED: AtomicBool = AtomicBool::new(false);
+
+#[command]
+fn record() {
+    RECORDED.store(true, Ordering::SeqCst);
+}
+
+fn serve() -> (tokio::runtime::Runtime, SocketAddr) {
+    serve_with_limits(Limits::default())
+}
+
+fn serve_with_limits(limits: Limits) -> (tokio::runtime::Runtime, SocketAddr) {
+    let rt = tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+    let config = Config::new()
+        .with_spawner(TokioSpawner::new(rt.handle().clone()))
+        .with_limits(limits);
+    let server = DevServer::new(commands!(add, negate, record), config);
+    let listener = rt
+        .block_on(tokio::net::TcpListener::bind("127.0.0.1:0"))
+        .unwrap();
+    let addr = listener.local_addr().unwrap();
+    rt.spawn(server.serve_on(listener));
+    (rt, addr)
+}
+
+struct HttpResponse {
+    status: u16,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+}
+
+impl HttpResponse {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(header, _)| header.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+fn send(addr: SocketAddr, method: &str, path: &str, origin: &str, body: &[u8]) -> HttpResponse {
+    let mut stream = TcpStream::connect(addr).unwrap();
+    write!(
+        stream,
+        "{method} {path} HTTP/1.1\r\nHost: {addr}\r\nOrigin: {origin}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        body.len()
+    )
+    .unwrap();
+    stream.write_all(body).unwrap();
+    let mut response: Vec<u8> = Vec::new();
+    stream.read_to_end(&mut response).unwrap();
+
+    let head_end: usize = response
+        .windows(4)
+        .position(|window| window == b"\r\n\r\n")
+        .unwrap();
+    let head = String::from_utf8(response[..head_end].to_vec()).unwrap();
+    let mut lines = head.split("\r\n");
+    let status: u16 = lines.next().unwrap()[9..12].parse().unwrap();
+    let headers: Vec<(String, String)> = lines
+        .map(|line| {
+            let (name, value) = line.split_once(':').unwrap();
+            (name.to_string(), value.trim().to_string())
+        })
+        .collect();
+    HttpResponse {
+        status,
+        headers,
+        body: response[head_end + 4..].to_vec(),
+    }
+}
+
+#[test]
+fn commands_are_served_over_http() {
+    let (_rt, addr) = serve();
+
+    let body = tauriless::serialize_to_vec_u8(&(1, 2)).unwrap();
+    let response = send(addr, "POST", "/add", VITE_DEV_ORIGIN, &body);
+    assert_eq!(response.status, 200);
+    assert_eq!(
+        tauriless::slice_to_deserialize::<i32>(&response.body).unwrap(),
+        3
+    );
+    assert_eq!(
+        response.header("access-control-allow-origin"),
+        Some(VITE_DEV_ORIGIN)
+    );
+
+    let body = tauriless::serialize_to_vec_u8(&(4,)).unwrap();
+    let response = send(addr, "POST", "/negate", VITE_DEV_ORIGIN, &body);
+    assert_eq!(response.status, 200);
+    assert_eq!(
+        tauriless::slice_to_deserialize::<i32>(&response.body).unwrap(),
+        -4
+    );
+
+    let response = send(addr, "POST", "/unknown", VITE_DEV_ORIGIN, &[]);
+    assert_eq!(response.status, 400);
+}
+
+#[test]
+fn only_the_dev_origin_is_allowed() {
+    let (_rt, addr) = serve();
+
+    let response = send(addr, "OPTIONS", "/add", VITE_DEV_ORIGIN, &[]);
+    assert_eq!(response.status, 204);
+    assert_eq!(
+        response.header("access-control-allow-origin"),
+        Some(VITE_DEV_ORIGIN)
+    );
+    assert_eq!(
+        response.header("access-control-allow-methods"),
+        Some("POST")
+    );
+
+    // The requests from the other origins never reach the commands, including the ones that aren't preflighted.
+    let body = tauriless::serialize_to_vec_u8(&()).unwrap();
+    let response = send(addr, "POST", "/record", "http://example.com", &body);
+    assert_eq!(response.status, 403);
+    assert_eq!(response.header("access-control-allow-origin"), None);
+    assert!(!RECORDED.load(Ordering::SeqCst));
+
+    let response = send(addr, "OPTIONS", "/record", "http://example.com", &[]);
+    assert_eq!(response.status, 403);
+    assert_eq!(response.header("access-control-allow-methods"), None);
+
+    let response = send(addr, "POST", "/record", VITE_DEV_ORIGIN, &body);
+    assert_eq!(response.status, 200);
+    assert!(RECORDED.load(Ordering::SeqCst));
+}
+
+#[test]
+fn bodies_are_read_up_to_the_limit() {
+    let (_rt, addr) = serve_with_limits(Limits {
+        max_body_size: 16,
+        ..Limits::default()
+    });
+
+    let response = send(addr, "POST", "/add", VITE_DEV_ORIGIN, &[0; 64]);
+    assert_eq!(response.status, 413);
+    // The body is rejected by the server before it's read in full.
+    assert!(String::from_utf8_lossy(&response.body).contains("exceeds the limit of 16 bytes"));
+    assert_eq!(
+        response.header("access-control-allow-origin"),
+        Some(VITE_DEV_ORIGIN)
+    );
+
+    let body = tauriless::serialize_to_vec_u8(&(1, 2)).unwrap();
+    let response = send(addr, "POST", "/add", VITE_DEV_ORIGIN, &body);
+    assert_eq!(response.status, 200);
+}
@@ -0,0 +1 @@
+console.log("Hello from the embedded assets!");
@@ -0,0 +1,70 @@
+use tauriless::http::{Request, Response};
+use tauriless::{Config, Limits, Router};
+use tauriless_common::url::command_to_url;
+use tauriless_macro::{command, commands};
+
+#[command]
+fn sum(values: Vec<serde_json::Value>) -> usize {
+    values.len()
+}
+
+fn rt() -> tokio::runtime::Runtime {
+    tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap()
+}
+
+fn router() -> Router {
+    let limits = Limits {
+        max_body_size: 1024,
+        max_depth: 4,
+        max_collection_len: 64,
+    };
+    Router::new(commands!(sum), Config::new().with_limits(limits))
+}
+
+fn invoke(router: &Router, values: serde_json::Value) -> Response<Vec<u8>> {
+    let request = Request::post(command_to_url("sum"))
+        .body(tauriless::serialize_to_vec_u8(&(values,)).unwrap())
+        .unwrap();
+    rt().block_on(router.handle(request))
+}
+
+fn reason(response: &Response<Vec<u8>>) -> String {
+    String::from_utf8_lossy(response.body()).into_owned()
+}
+
+#[test]
+fn arguments_within_the_limits_are_accepted() {
+    let response = invoke(&router(), serde_json::json!([1, [2], "three"]));
+    assert_eq!(response.status(), 200);
+    let len: usize = tauriless::slice_to_deserialize(response.body()).unwrap();
+    assert_eq!(len, 3);
+}
+
+#[test]
+fn large_bodies_are_payload_too_large() {
+    let response = invoke(&router(), serde_json::json!(["a".repeat(2048)]));
+    assert_eq!(response.status(), 413);
+    assert!(reason(&response).contains("exceeds the limit of 1024 bytes"));
+}
+
+#[test]
+fn deep_or_long_arguments_are_bad_requests() {
+    let response = invoke(&router(), serde_json::json!([[[[1]]]]));
+    assert_eq!(response.status(), 400);
+    assert!(reason(&response).contains("nested deeper than the limit of 4 levels"));
+
+    let response = invoke(&router(), serde_json::json!(vec![0; 65]));
+    assert_eq!(response.status(), 400);
+    assert!(reason(&response).contains("exceeds the limit of 64 elements"));
+}
+
+#[test]
+fn default_limits_apply() {
+    let router = Router::new(commands!(sum), Config::new());
+    let response = invoke(&router, serde_json::json!(vec![0; 1000]));
+    assert_eq!(response.status(), 200);
+    let response = invoke(&router, serde_json::json!(["a".repeat(17 * 1024 * 1024)]));
+    assert_eq!(response.status(), 413);
+}
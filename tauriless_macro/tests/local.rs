@@ -0,0 +1,155 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::mpsc;
+
+use tauriless::http::{Request, Response};
+use tauriless::{Command, Config, Router, SpawnedLocal, Spawner, StateManager, TokioSpawner};
+use tauriless_common::url::command_to_url;
+use tauriless_macro::{command, commands};
+
+async fn yield_now() {
+    let mut yielded = false;
+    std::future::poll_fn(|cx| {
+        if yielded {
+            std::task::Poll::Ready(())
+        } else {
+            yielded = true;
+            cx.waker().wake_by_ref();
+            std::task::Poll::Pending
+        }
+    })
+    .await
+}
+
+#[command(local)]
+async fn count_to(n: u32) -> u32 {
+    // `Rc<RefCell<_>>` is held across an `.await`, so the future is not `Send`.
+    let counter = Rc::new(RefCell::new(0));
+    for _ in 0..n {
+        *counter.borrow_mut() += 1;
+        yield_now().await;
+    }
+    let count = *counter.borrow();
+    count
+}
+
+#[command]
+async fn double(n: u32) -> u32 {
+    n * 2
+}
+
+// Panics while the arguments are deserialized, i.e. on the thread of the local executor but outside of the command.
+struct Explosive;
+
+impl<'de> serde::Deserialize<'de> for Explosive {
+    fn deserialize<D: serde::Deserializer<'de>>(_deserializer: D) -> Result<Self, D::Error> {
+        panic!("explosive argument");
+    }
+}
+
+#[command(local)]
+async fn defuse(_explosive: Explosive) {}
+
+fn spawn_count_to(spawner: &dyn Spawner, n: u32) -> u32 {
+    let (tx, rx) = mpsc::channel::<u32>();
+    let f: SpawnedLocal = Box::new(move || {
+        Box::pin(async move {
+            let state = StateManager::new();
+            let ret = <__command_count_to as Command>::local_async_command(
+                (n,),
+                &state,
+                &tauriless::CancellationToken::new(),
+            )
+            .await;
+            tx.send(ret).unwrap();
+        })
+    });
+    assert!(spawner.spawn_local(f).is_ok());
+    rx.recv().unwrap()
+}
+
+const _: () = assert!(<__command_count_to as Command>::IS_LOCAL);
+const _: () = assert!(!<__command_double as Command>::IS_LOCAL);
+
+#[test]
+fn local_commands_run_on_tokio_local_set() {
+    let rt = tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(1)
+        .build()
+        .unwrap();
+    let config = Config::new().with_spawner(TokioSpawner::new(rt.handle().clone()));
+    assert_eq!(spawn_count_to(config.spawner(), 3), 3);
+    // The thread driving the `LocalSet` is reused.
+    assert_eq!(spawn_count_to(config.spawner(), 5), 5);
+}
+
+struct NoLocalSpawner;
+
+impl Spawner for NoLocalSpawner {
+    fn spawn(&self, _future: tauriless::SpawnedFuture) {
+        unimplemented!()
+    }
+
+    fn spawn_blocking(&self, _f: tauriless::SpawnedBlocking) {
+        unimplemented!()
+    }
+}
+
+#[test]
+fn local_commands_run_on_default_local_executor() {
+    assert_eq!(spawn_count_to(&NoLocalSpawner, 3), 3);
+    assert_eq!(spawn_count_to(&NoLocalSpawner, 4), 4);
+}
+
+fn invoke<T: serde::Serialize>(config: Config, command: &str, args: &T) -> Response<Vec<u8>> {
+    let rt = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+    let router = Router::new(commands!(count_to, defuse), config);
+    let request = Request::post(command_to_url(command))
+        .body(tauriless::serialize_to_vec_u8(args).unwrap())
+        .unwrap();
+    rt.block_on(router.handle(request))
+}
+
+#[test]
+fn panics_outside_of_local_commands_are_answered() {
+    let response = invoke(Config::new().with_spawner(NoLocalSpawner), "defuse", &((),));
+    assert_eq!(response.status(), 500);
+    // The local executor keeps running the later tasks.
+    let response = invoke(
+        Config::new().with_spawner(NoLocalSpawner),
+        "count_to",
+        &(2,),
+    );
+    assert_eq!(response.status(), 200);
+    let count: u32 = tauriless::slice_to_deserialize(response.body()).unwrap();
+    assert_eq!(count, 2);
+}
+
+struct StoppedLocalSpawner;
+
+impl Spawner for StoppedLocalSpawner {
+    fn spawn(&self, _future: tauriless::SpawnedFuture) {
+        unimplemented!()
+    }
+
+    fn spawn_blocking(&self, _f: tauriless::SpawnedBlocking) {
+        unimplemented!()
+    }
+
+    fn spawn_local(&self, f: SpawnedLocal) -> Result<(), SpawnedLocal> {
+        Err(f)
+    }
+}
+
+#[test]
+fn stopped_local_executor_is_reported() {
+    let response = invoke(
+        Config::new().with_spawner(StoppedLocalSpawner),
+        "count_to",
+        &(2,),
+    );
+    assert_eq!(response.status(), 500);
+    assert!(String::from_utf8_lossy(response.body()).contains("not running"));
+}
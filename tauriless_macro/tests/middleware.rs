@@ -0,0 +1,195 @@
+use std::sync::{Arc, Mutex};
+
+use tauriless::http::{header, HeaderValue, Request, Response, StatusCode};
+use tauriless::{Call, CommandRegistry, Config, Next, Plugin, Router};
+use tauriless_common::url::command_to_url;
+use tauriless_macro::{command, commands};
+
+#[command]
+fn add(a: i32, b: i32) -> i32 {
+    a + b
+}
+
+#[command]
+fn delete_everything() {}
+
+#[command]
+fn list() -> Vec<String> {
+    vec!["a.txt".to_string()]
+}
+
+struct FsPlugin;
+
+impl Plugin for FsPlugin {
+    fn name(&self) -> &'static str {
+        "fs"
+    }
+
+    fn commands(&self) -> CommandRegistry {
+        let mut commands = commands!(list);
+        commands.middleware(|call: Call, next: Next| {
+            let call = call.map_response(|mut response| {
+                let headers = response.headers_mut();
+                headers.insert("x-plugin", HeaderValue::from_static("fs"));
+                response
+            });
+            next.run(call)
+        });
+        commands
+    }
+}
+
+fn rt() -> tokio::runtime::Runtime {
+    tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap()
+}
+
+fn invoke<T: serde::Serialize>(router: &Router, command: &str, args: &T) -> Response<Vec<u8>> {
+    let request = Request::post(command_to_url(command))
+        .body(tauriless::serialize_to_vec_u8(args).unwrap())
+        .unwrap();
+    rt().block_on(router.handle(request))
+}
+
+// Records the names of the commands passing through the middleware.
+fn recorder(log: &Arc<Mutex<Vec<String>>>, tag: &'static str) -> impl Fn(Call, Next) + Send + Sync {
+    let log: Arc<Mutex<Vec<String>>> = log.clone();
+    move |call: Call, next: Next| {
+        log.lock()
+            .unwrap()
+            .push(format!("{tag}:{}", call.command()));
+        next.run(call)
+    }
+}
+
+#[test]
+fn middlewares_are_applied_in_order() {
+    let log: Arc<Mutex<Vec<String>>> = Arc::default();
+    let mut commands = commands!(add);
+    commands
+        .middleware(recorder(&log, "outer"))
+        .middleware(recorder(&log, "inner"));
+    let router = Router::new(commands, Config::new());
+
+    let response = invoke(&router, "add", &(1, 2));
+    assert_eq!(response.status(), 200);
+    assert_eq!(*log.lock().unwrap(), ["outer:add", "inner:add"]);
+
+    // The unknown commands don't pass through the middlewares.
+    assert_eq!(invoke(&router, "missing", &()).status(), 400);
+    assert_eq!(log.lock().unwrap().len(), 2);
+}
+
+#[test]
+fn middlewares_can_respond_without_invoking_the_command() {
+    let mut commands = commands!(add, delete_everything);
+    commands.middleware(|call: Call, next: Next| {
+        let is_admin: bool = call
+            .headers()
+            .get("x-role")
+            .is_some_and(|role| role == "admin");
+        if call.command() == "delete-everything" && !is_admin {
+            let response = Response::builder()
+                .status(StatusCode::FORBIDDEN)
+                .body(b"Forbidden: admins only.".as_slice().into())
+                .unwrap();
+            return call.respond(response);
+        }
+        next.run(call)
+    });
+    let router = Router::new(commands, Config::new());
+
+    assert_eq!(invoke(&router, "add", &(1, 2)).status(), 200);
+    let response = invoke(&router, "delete_everything", &());
+    assert_eq!(response.status(), 403);
+    assert_eq!(response.headers()[header::ACCESS_CONTROL_ALLOW_ORIGIN], "*");
+
+    let request = Request::post(command_to_url("delete_everything"))
+        .header("x-role", "admin")
+        .body(tauriless::serialize_to_vec_u8(&()).unwrap())
+        .unwrap();
+    assert_eq!(rt().block_on(router.handle(request)).status(), 200);
+}
+
+#[test]
+fn middlewares_can_replace_the_arguments_and_the_response() {
+    let mut commands = commands!(add);
+    commands.middleware(|mut call: Call, next: Next| {
+        let (a, b): (i32, i32) = tauriless::slice_to_deserialize(call.body()).unwrap();
+        *call.body_mut() = tauriless::serialize_to_vec_u8(&(a.abs(), b.abs())).unwrap();
+        next.run(call.map_response(|mut response| {
+            let headers = response.headers_mut();
+            headers.insert("x-clamped", HeaderValue::from_static("true"));
+            response
+        }))
+    });
+    let router = Router::new(commands, Config::new());
+
+    let response = invoke(&router, "add", &(-1, -2));
+    assert_eq!(response.headers()["x-clamped"], "true");
+    let sum: i32 = tauriless::slice_to_deserialize(response.body()).unwrap();
+    assert_eq!(sum, 3);
+}
+
+#[test]
+fn middlewares_can_pass_the_call_on_from_another_thread() {
+    let mut commands = commands!(add);
+    commands.middleware(|call: Call, next: Next| {
+        std::thread::spawn(move || next.run(call));
+    });
+    let router = Router::new(commands, Config::new());
+    assert_eq!(invoke(&router, "add", &(1, 2)).status(), 200);
+}
+
+#[test]
+fn middlewares_apply_only_to_the_commands_of_their_registry() {
+    let log: Arc<Mutex<Vec<String>>> = Arc::default();
+    let mut other = commands!(delete_everything);
+    other.middleware(recorder(&log, "other"));
+    let mut commands = commands!(add);
+    commands
+        .middleware(recorder(&log, "app"))
+        .merge(other)
+        .plugin(FsPlugin);
+    let router = Router::new(commands, Config::new());
+
+    for command in ["add", "delete_everything", "plugin:fs|list"] {
+        let response = invoke(&router, command, &serde_json::json!([]));
+        let from_plugin: bool = response.headers().contains_key("x-plugin");
+        assert_eq!(from_plugin, command == "plugin:fs|list");
+    }
+    assert_eq!(
+        *log.lock().unwrap(),
+        [
+            "app:add",
+            "app:delete-everything",
+            "other:delete-everything",
+            "app:plugin:fs|list"
+        ]
+    );
+}
+
+#[test]
+fn panicking_middlewares_are_answered() {
+    let mut commands = commands!(add, delete_everything);
+    commands.middleware(|call: Call, next: Next| {
+        if call.command() == "delete-everything" {
+            panic!("middleware panic");
+        }
+        next.run(call)
+    });
+    let router = Router::new(commands, Config::new());
+
+    let response = invoke(&router, "delete_everything", &());
+    assert_eq!(response.status(), 500);
+    assert_eq!(response.headers()[header::ACCESS_CONTROL_ALLOW_ORIGIN], "*");
+    if cfg!(debug_assertions) {
+        assert_eq!(
+            response.body().as_slice(),
+            b"Internal server error: the dispatch of the `delete-everything` command panicked: middleware panic."
+        );
+    }
+    // The router keeps serving the other requests.
+    assert_eq!(invoke(&router, "add", &(1, 2)).status(), 200);
+}
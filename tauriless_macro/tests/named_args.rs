@@ -0,0 +1,67 @@
+use tauriless::{Command, State, StateManager};
+use tauriless_macro::command;
+
+struct Offset(i32);
+
+#[command]
+fn subtract(minuend: i32, subtrahend: i32) -> i32 {
+    minuend - subtrahend
+}
+
+#[command(rename_all = "camelCase")]
+fn subtract_with_offset(first_operand: i32, offset: State<Offset>, second_operand: i32) -> i32 {
+    first_operand - second_operand + offset.0
+}
+
+#[command]
+fn argsless() -> i32 {
+    42
+}
+
+fn encode(value: serde_json::Value) -> Vec<u8> {
+    pot::to_vec(&value).unwrap()
+}
+
+#[test]
+fn positional_args_are_accepted() {
+    let body = encode(serde_json::json!([3, 1]));
+    let args = tauriless::deserialize_args::<__command_subtract>(&body).unwrap();
+    assert_eq!(args, (3, 1));
+}
+
+#[test]
+fn named_args_are_accepted() {
+    let body = encode(serde_json::json!({ "subtrahend": 1, "minuend": 3 }));
+    let args = tauriless::deserialize_args::<__command_subtract>(&body).unwrap();
+    assert_eq!(args, (3, 1));
+}
+
+#[test]
+fn named_args_can_be_camel_cased() {
+    let state = StateManager::new().with(Offset(10));
+    let body = encode(serde_json::json!({ "secondOperand": 1, "firstOperand": 3 }));
+    let args = tauriless::deserialize_args::<__command_subtract_with_offset>(&body).unwrap();
+    assert_eq!(
+        <__command_subtract_with_offset as Command>::sync_command(
+            args,
+            &state,
+            &tauriless::CancellationToken::new()
+        ),
+        12
+    );
+
+    let body = encode(serde_json::json!({ "second_operand": 1, "first_operand": 3 }));
+    assert!(tauriless::deserialize_args::<__command_subtract_with_offset>(&body).is_err());
+}
+
+#[test]
+fn missing_named_args_are_rejected() {
+    let body = encode(serde_json::json!({ "minuend": 3 }));
+    assert!(tauriless::deserialize_args::<__command_subtract>(&body).is_err());
+}
+
+#[test]
+fn argsless_commands_accept_an_empty_map() {
+    let body = encode(serde_json::json!({}));
+    tauriless::deserialize_args::<__command_argsless>(&body).unwrap();
+}
@@ -0,0 +1,87 @@
+use tauriless::Command;
+use tauriless_macro::command;
+
+#[command]
+fn greet(name: String, greeting: Option<String>, #[tauriless(default)] times: usize) -> String {
+    let greeting = greeting.unwrap_or_else(|| "Hello".to_string());
+    vec![format!("{greeting}, {name}!"); times.max(1)].join(" ")
+}
+
+#[command]
+fn all_optional(a: Option<i32>, #[tauriless(default)] b: i32) -> i32 {
+    a.unwrap_or(1) + b
+}
+
+#[command]
+fn optional_in_the_middle(a: Option<i32>, b: i32) -> i32 {
+    a.unwrap_or(1) + b
+}
+
+fn call<C: Command>(value: serde_json::Value) -> Result<C::RetTy, String> {
+    let body = pot::to_vec(&value).unwrap();
+    let args = tauriless::deserialize_args::<C>(&body).map_err(|e| e.to_string())?;
+    Ok(C::sync_command(
+        args,
+        &tauriless::StateManager::new(),
+        &tauriless::CancellationToken::new(),
+    ))
+}
+
+#[test]
+fn trailing_optional_args_can_be_omitted_positionally() {
+    assert_eq!(<__command_greet as Command>::MIN_ARITY, 1);
+    assert_eq!(
+        call::<__command_greet>(serde_json::json!(["Alice"])).unwrap(),
+        "Hello, Alice!"
+    );
+    assert_eq!(
+        call::<__command_greet>(serde_json::json!(["Alice", "Hi"])).unwrap(),
+        "Hi, Alice!"
+    );
+    assert_eq!(
+        call::<__command_greet>(serde_json::json!(["Alice", null, 2])).unwrap(),
+        "Hello, Alice! Hello, Alice!"
+    );
+    assert_eq!(
+        call::<__command_greet>(serde_json::json!([])).unwrap_err(),
+        "expected 1 to 3 arguments, got 0"
+    );
+}
+
+#[test]
+fn optional_args_can_be_omitted_by_name() {
+    assert_eq!(
+        call::<__command_greet>(serde_json::json!({ "name": "Bob", "times": 2 })).unwrap(),
+        "Hello, Bob! Hello, Bob!"
+    );
+    assert_eq!(
+        call::<__command_optional_in_the_middle>(serde_json::json!({ "b": 2 })).unwrap(),
+        3
+    );
+}
+
+#[test]
+fn commands_with_only_optional_args_can_be_invoked_without_args() {
+    assert_eq!(<__command_all_optional as Command>::MIN_ARITY, 0);
+    assert_eq!(
+        call::<__command_all_optional>(serde_json::Value::Null).unwrap(),
+        1
+    );
+    assert_eq!(
+        call::<__command_all_optional>(serde_json::json!([])).unwrap(),
+        1
+    );
+    assert_eq!(
+        call::<__command_all_optional>(serde_json::json!([2, 3])).unwrap(),
+        5
+    );
+}
+
+#[test]
+fn non_trailing_optional_args_are_required_positionally() {
+    assert_eq!(<__command_optional_in_the_middle as Command>::MIN_ARITY, 2);
+    assert_eq!(
+        call::<__command_optional_in_the_middle>(serde_json::json!([null, 2])).unwrap(),
+        3
+    );
+}
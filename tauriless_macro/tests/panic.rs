@@ -0,0 +1,115 @@
+use tauriless::http::Request;
+use tauriless::{Command, Config, Router, StateManager, TokioSpawner};
+use tauriless_common::url::command_to_url;
+use tauriless_macro::{command, commands};
+
+struct Unmanaged;
+
+#[command]
+fn sync_panic(n: i32) -> i32 {
+    panic!("sync panic with {n}");
+}
+
+#[command]
+async fn async_panic() -> i32 {
+    panic!("async panic");
+}
+
+// Panics while the return value is serialized, i.e. after the command has returned.
+struct Unserializable;
+
+impl serde::Serialize for Unserializable {
+    fn serialize<S: serde::Serializer>(&self, _serializer: S) -> Result<S::Ok, S::Error> {
+        panic!("unserializable return value");
+    }
+}
+
+#[command]
+fn sync_unserializable() -> Unserializable {
+    Unserializable
+}
+
+#[command]
+async fn async_unserializable() -> Unserializable {
+    Unserializable
+}
+
+#[command]
+async fn async_unmanaged_state(_unmanaged: tauriless::State<Unmanaged>) -> i32 {
+    0
+}
+
+#[test]
+fn sync_panics_are_caught() {
+    let state = StateManager::new();
+    let ret = tauriless::catch_unwind(|| {
+        <__command_sync_panic as Command>::sync_command(
+            (1,),
+            &state,
+            &tauriless::CancellationToken::new(),
+        )
+    });
+    let payload = ret.unwrap_err();
+    let response =
+        tauriless::handle_command_panic(<__command_sync_panic as Command>::NAME, payload);
+    assert_eq!(response.status().as_u16(), 500);
+    if cfg!(debug_assertions) {
+        assert_eq!(
+            response.body().as_ref(),
+            b"Internal server error: the `sync_panic` command panicked: sync panic with 1."
+        );
+    }
+}
+
+#[test]
+fn async_panics_are_caught() {
+    let rt = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+    let state = StateManager::new();
+
+    let ret = rt.block_on(tauriless::CatchUnwind::new(async {
+        <__command_async_panic as Command>::async_command(
+            (),
+            &state,
+            &tauriless::CancellationToken::new(),
+        )
+        .await
+    }));
+    let payload = ret.unwrap_err();
+    let response =
+        tauriless::handle_command_panic(<__command_async_panic as Command>::NAME, payload);
+    assert_eq!(response.status().as_u16(), 500);
+
+    // The `State` arguments are extracted when the future is created.
+    let ret = rt.block_on(tauriless::CatchUnwind::new(async {
+        <__command_async_unmanaged_state as Command>::async_command(
+            (),
+            &state,
+            &tauriless::CancellationToken::new(),
+        )
+        .await
+    }));
+    assert!(ret.is_err());
+}
+
+#[test]
+fn serialization_panics_are_caught() {
+    let rt = tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(1)
+        .build()
+        .unwrap();
+    let config = Config::new().with_spawner(TokioSpawner::new(rt.handle().clone()));
+    let router = Router::new(commands!(sync_unserializable, async_unserializable), config);
+    for command in ["sync_unserializable", "async_unserializable"] {
+        let request = Request::post(command_to_url(command))
+            .body(tauriless::serialize_to_vec_u8(&()).unwrap())
+            .unwrap();
+        let response = rt.block_on(router.handle(request));
+        assert_eq!(response.status(), 500);
+        if cfg!(debug_assertions) {
+            let body = String::from_utf8_lossy(response.body());
+            assert!(body.ends_with("panicked: unserializable return value."));
+        }
+    }
+}
@@ -0,0 +1,75 @@
+use tauriless::{CommandRegistry, Plugin, State, StateManager};
+use tauriless_macro::{command, commands};
+
+struct Greeting(String);
+
+#[command]
+fn greet(name: String, greeting: State<Greeting>) -> String {
+    format!("{}, {name}!", greeting.0)
+}
+
+#[command]
+fn read_file(path: String) -> Result<String, String> {
+    std::fs::read_to_string(path).map_err(|e| e.to_string())
+}
+
+struct GreeterPlugin;
+
+impl Plugin for GreeterPlugin {
+    fn name(&self) -> &'static str {
+        "greeter"
+    }
+
+    fn commands(&self) -> CommandRegistry {
+        commands!(greet)
+    }
+
+    fn state(&self) -> StateManager {
+        StateManager::new().with(Greeting("Hello".to_string()))
+    }
+}
+
+struct FsPlugin;
+
+impl Plugin for FsPlugin {
+    fn name(&self) -> &'static str {
+        "file_system"
+    }
+
+    fn commands(&self) -> CommandRegistry {
+        let mut registry = commands!(read_file);
+        registry.plugin(GreeterPlugin);
+        registry
+    }
+}
+
+#[test]
+fn plugin_commands_are_namespaced() {
+    let mut registry = commands!(read_file);
+    registry.plugin(GreeterPlugin);
+    assert_eq!(registry.len(), 2);
+    assert!(registry.contains("read_file"));
+    assert!(registry.contains("plugin:greeter|greet"));
+    assert!(!registry.contains("greet"));
+}
+
+#[test]
+fn nested_plugins_are_namespaced() {
+    let mut registry = CommandRegistry::new();
+    registry.plugin(FsPlugin);
+    assert_eq!(registry.len(), 2);
+    // The underscores are replaced with dashes just like in the names of the commands.
+    assert!(registry.contains("plugin:file-system|read-file"));
+    assert!(registry.contains("plugin:file_system|plugin:greeter|greet"));
+}
+
+#[test]
+fn plugins_are_merged() {
+    let mut plugins = CommandRegistry::new();
+    plugins.plugin(GreeterPlugin).plugin(FsPlugin);
+    let mut registry = commands!(read_file);
+    registry.merge(plugins);
+    assert_eq!(registry.len(), 4);
+    assert!(registry.contains("plugin:greeter|greet"));
+    assert!(registry.contains("plugin:file_system|read_file"));
+}
@@ -0,0 +1,71 @@
+use std::rc::Rc;
+
+use tauriless::{CancellationToken, CommandRegistry, Commands, State};
+use tauriless_macro::{command, commands};
+
+struct Multiplier(i32);
+
+#[command]
+fn sync_command(n: i32) -> i32 {
+    n
+}
+
+#[command(blocking)]
+fn blocking_command(n: i32, token: CancellationToken) -> Option<i32> {
+    (!token.is_cancelled()).then_some(n)
+}
+
+#[command]
+async fn async_command(n: i32, multiplier: State<Multiplier>) -> Result<i32, String> {
+    Ok(n * multiplier.0)
+}
+
+#[command(local)]
+async fn local_command(n: i32) -> i32 {
+    let n = Rc::new(n);
+    std::future::ready(()).await;
+    *n
+}
+
+fn is_commands<C: Commands>(_: &C) {}
+
+#[test]
+fn commands_macro_creates_registry() {
+    let registry: CommandRegistry =
+        commands!(sync_command, blocking_command, async_command, local_command);
+    is_commands(&registry);
+    assert_eq!(registry.len(), 4);
+    assert!(registry.contains("sync_command"));
+    assert!(registry.contains("blocking-command"));
+    assert!(registry.contains("async_command"));
+    assert!(registry.contains("local_command"));
+    assert!(!registry.contains("unknown_command"));
+}
+
+#[test]
+fn commands_are_registered_at_runtime() {
+    let mut registry = CommandRegistry::new();
+    assert!(registry.is_empty());
+    registry
+        .register::<__command_sync_command>()
+        .register_fn("add", |(a, b): (i32, i32)| a + b);
+    if cfg!(any()) {
+        registry.register::<__command_async_command>();
+    }
+    assert_eq!(registry.len(), 2);
+    assert!(registry.contains("sync_command"));
+    assert!(registry.contains("add"));
+    assert!(!registry.contains("async_command"));
+}
+
+#[test]
+fn registries_are_merged() {
+    let mut registry = commands!(sync_command, async_command);
+    registry.merge(commands!(async_command, local_command));
+    registry.merge(CommandRegistry::new());
+    // The commands with the same names are replaced rather than duplicated.
+    assert_eq!(registry.len(), 3);
+    assert!(registry.contains("sync_command"));
+    assert!(registry.contains("async_command"));
+    assert!(registry.contains("local_command"));
+}
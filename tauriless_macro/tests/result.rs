@@ -0,0 +1,79 @@
+use serde::{Deserialize, Serialize};
+use tauriless::{Command, StateManager};
+use tauriless_macro::command;
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+enum DivisionError {
+    DivisionByZero,
+}
+
+#[command]
+fn divide(a: i32, b: i32) -> Result<i32, DivisionError> {
+    if b == 0 {
+        return Err(DivisionError::DivisionByZero);
+    }
+    Ok(a / b)
+}
+
+#[command]
+fn double(n: i32) -> i32 {
+    n * 2
+}
+
+#[test]
+fn result_commands_are_split_into_ok_and_err() {
+    let state = StateManager::new();
+
+    let ret = <__command_divide as Command>::sync_command(
+        (6, 3),
+        &state,
+        &tauriless::CancellationToken::new(),
+    );
+    assert_eq!(<__command_divide as Command>::into_result(ret), Ok(2));
+
+    let ret = <__command_divide as Command>::sync_command(
+        (6, 0),
+        &state,
+        &tauriless::CancellationToken::new(),
+    );
+    assert_eq!(
+        <__command_divide as Command>::into_result(ret),
+        Err(DivisionError::DivisionByZero)
+    );
+}
+
+#[test]
+fn err_values_are_sent_with_a_distinct_status() {
+    let state = StateManager::new();
+
+    let ret = <__command_divide as Command>::sync_command(
+        (6, 3),
+        &state,
+        &tauriless::CancellationToken::new(),
+    );
+    let response = tauriless::handle_command_return::<__command_divide>(ret);
+    assert_eq!(response.status().as_u16(), 200);
+
+    let ret = <__command_divide as Command>::sync_command(
+        (6, 0),
+        &state,
+        &tauriless::CancellationToken::new(),
+    );
+    let response = tauriless::handle_command_return::<__command_divide>(ret);
+    assert_eq!(
+        response.status().as_u16(),
+        tauriless_common::status::COMMAND_ERROR
+    );
+    let err: DivisionError = pot::from_slice(response.body()).unwrap();
+    assert_eq!(err, DivisionError::DivisionByZero);
+
+    let ret = <__command_double as Command>::sync_command(
+        (21,),
+        &state,
+        &tauriless::CancellationToken::new(),
+    );
+    let response = tauriless::handle_command_return::<__command_double>(ret);
+    assert_eq!(response.status().as_u16(), 200);
+    let ok: i32 = pot::from_slice(response.body()).unwrap();
+    assert_eq!(ok, 42);
+}
@@ -0,0 +1,227 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use tauriless::http::{Request, Response};
+use tauriless::{
+    CancellationToken, CommandRegistry, Config, Plugin, Router, State, StateManager, TokioSpawner,
+};
+use tauriless_common::status::{COMMAND_ERROR, COMMAND_TIMEOUT};
+use tauriless_common::url::{cancellation_url, command_to_url, command_to_url_with_invocation_id};
+use tauriless_macro::{command, commands};
+
+struct Multiplier(i32);
+
+#[command]
+fn add(a: i32, b: i32) -> i32 {
+    a + b
+}
+
+#[command]
+fn divide(a: i32, b: i32) -> Result<i32, String> {
+    a.checked_div(b)
+        .ok_or_else(|| "division by zero".to_string())
+}
+
+#[command]
+fn sync_panic() {
+    panic!("sync panic");
+}
+
+#[command(blocking)]
+fn blocking_multiply(n: i32, multiplier: State<Multiplier>) -> i32 {
+    n * multiplier.0
+}
+
+#[command]
+async fn async_multiply(n: i32, multiplier: State<Multiplier>) -> i32 {
+    n * multiplier.0
+}
+
+#[command(local)]
+async fn local_negate(n: i32) -> i32 {
+    let n = std::rc::Rc::new(n);
+    std::future::ready(()).await;
+    -*n
+}
+
+#[command(timeout = "10ms")]
+async fn never_returns() -> i32 {
+    std::future::pending::<()>().await;
+    0
+}
+
+#[command]
+async fn returns_on_cancellation(token: CancellationToken) -> i32 {
+    token.cancelled().await;
+    -1
+}
+
+fn request<T: serde::Serialize>(url: &str, args: &T) -> Request<Vec<u8>> {
+    Request::post(url)
+        .body(tauriless::serialize_to_vec_u8(args).unwrap())
+        .unwrap()
+}
+
+fn body<T: for<'a> serde::Deserialize<'a>>(response: &Response<Vec<u8>>) -> T {
+    tauriless::slice_to_deserialize(response.body()).unwrap()
+}
+
+fn router(commands: CommandRegistry) -> (tokio::runtime::Runtime, Router) {
+    let rt = tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+    let config = Config::new()
+        .with_state(StateManager::new().with(Multiplier(3)))
+        .with_spawner(TokioSpawner::new(rt.handle().clone()));
+    (rt, Router::new(commands, config))
+}
+
+#[test]
+fn sync_commands_are_dispatched() {
+    let (rt, router) = router(commands!(add, divide, sync_panic));
+
+    let response = rt.block_on(router.handle(request(&command_to_url("add"), &(1, 2))));
+    assert_eq!(response.status(), 200);
+    assert_eq!(body::<i32>(&response), 3);
+
+    let args = serde_json::json!({ "a": 6, "b": 3 });
+    let response = rt.block_on(router.handle(request(&command_to_url("divide"), &args)));
+    assert_eq!(response.status(), 200);
+    assert_eq!(body::<i32>(&response), 2);
+
+    let response = rt.block_on(router.handle(request(&command_to_url("divide"), &(1, 0))));
+    assert_eq!(response.status(), COMMAND_ERROR);
+    assert_eq!(body::<String>(&response), "division by zero");
+
+    let response = rt.block_on(router.handle(request(&command_to_url("sync_panic"), &())));
+    assert_eq!(response.status(), 500);
+}
+
+#[test]
+fn invalid_requests_are_rejected() {
+    let (rt, router) = router(commands!(add));
+
+    let response = rt.block_on(router.handle(request(&command_to_url("unknown"), &())));
+    assert_eq!(response.status(), 400);
+
+    let response = rt.block_on(router.handle(request(&command_to_url("add"), &(1,))));
+    assert_eq!(response.status(), 400);
+}
+
+#[test]
+fn spawned_commands_are_dispatched() {
+    let (rt, router) = router(commands!(blocking_multiply, async_multiply, local_negate));
+
+    let response = rt.block_on(router.handle(request(&command_to_url("blocking_multiply"), &(2,))));
+    assert_eq!(response.status(), 200);
+    assert_eq!(body::<i32>(&response), 6);
+
+    let response = rt.block_on(router.handle(request(&command_to_url("async_multiply"), &(4,))));
+    assert_eq!(response.status(), 200);
+    assert_eq!(body::<i32>(&response), 12);
+
+    let response = rt.block_on(router.handle(request(&command_to_url("local_negate"), &(5,))));
+    assert_eq!(response.status(), 200);
+    assert_eq!(body::<i32>(&response), -5);
+}
+
+#[test]
+fn commands_time_out() {
+    let (rt, router) = router(commands!(never_returns));
+    let response = rt.block_on(router.handle(request(&command_to_url("never_returns"), &())));
+    assert_eq!(response.status(), COMMAND_TIMEOUT);
+}
+
+#[test]
+fn commands_are_cancelled() {
+    let (rt, router) = router(commands!(returns_on_cancellation));
+    let url = command_to_url_with_invocation_id("returns_on_cancellation", "42");
+    let invocation = rt.spawn({
+        let router = router.clone();
+        async move { router.handle(request(&url, &())).await }
+    });
+    // The invocation may not have been registered yet, in which case the cancellation is retried.
+    let response = rt.block_on(async {
+        loop {
+            let cancellation = Request::post(cancellation_url("42"))
+                .body(Vec::new())
+                .unwrap();
+            let response = router.handle(cancellation).await;
+            assert_eq!(response.status(), 204);
+            if invocation.is_finished() {
+                break invocation.await.unwrap();
+            }
+            std::thread::sleep(Duration::from_millis(1));
+        }
+    });
+    // The command observes the cancellation, so it returns normally.
+    assert_eq!(response.status(), 200);
+    assert_eq!(body::<i32>(&response), -1);
+}
+
+struct Prefix(&'static str);
+
+#[command]
+fn greet(name: String, prefix: State<Prefix>) -> String {
+    format!("{}{name}", prefix.0)
+}
+
+struct GreeterPlugin {
+    set_up: Arc<AtomicBool>,
+}
+
+impl Plugin for GreeterPlugin {
+    fn name(&self) -> &'static str {
+        "greeter"
+    }
+
+    fn commands(&self) -> CommandRegistry {
+        commands!(greet)
+    }
+
+    fn state(&self) -> StateManager {
+        StateManager::new().with(Prefix("Hello, "))
+    }
+
+    fn setup(&mut self, state: &StateManager) {
+        assert!(state.try_get::<Prefix>().is_some());
+        self.set_up.store(true, Ordering::Relaxed);
+    }
+}
+
+#[test]
+fn plugin_commands_are_dispatched() {
+    let set_up = Arc::new(AtomicBool::new(false));
+    let mut commands = commands!(greet);
+    commands.plugin(GreeterPlugin {
+        set_up: set_up.clone(),
+    });
+    let (rt, router) = router(commands);
+    assert!(set_up.load(Ordering::Relaxed));
+
+    let response =
+        rt.block_on(router.handle(request(&command_to_url("plugin:greeter|greet"), &("Bob",))));
+    assert_eq!(response.status(), 200);
+    assert_eq!(body::<String>(&response), "Hello, Bob");
+
+    let url = "http://tauiriless.localhost/plugin%3Agreeter%7Cgreet";
+    let response = rt.block_on(router.handle(request(url, &("Alice",))));
+    assert_eq!(body::<String>(&response), "Hello, Alice");
+
+    // The state of the plugin is not available to the commands of the application.
+    let response = rt.block_on(router.handle(request(&command_to_url("greet"), &("Bob",))));
+    assert_eq!(response.status(), 500);
+}
+
+#[test]
+fn closures_are_dispatched() {
+    let mut commands = CommandRegistry::new();
+    commands.register_fn("concat", |(a, b): (String, String)| a + &b);
+    let (rt, router) = router(commands);
+
+    let response = rt.block_on(router.handle(request(&command_to_url("concat"), &("a", "b"))));
+    assert_eq!(response.status(), 200);
+    assert_eq!(body::<String>(&response), "ab");
+}
@@ -0,0 +1,157 @@
+use tauriless::http::{header, Request, Response};
+use tauriless::testing::{InvokeError, TestClient};
+use tauriless::{Config, Router, Security};
+use tauriless_common::auth::AUTH_TOKEN_HEADER;
+use tauriless_common::url::command_to_url;
+use tauriless_macro::{command, commands};
+
+const APP_ORIGIN: &str = "http://tauriless-assets.localhost";
+
+#[command]
+fn add(a: i32, b: i32) -> i32 {
+    a + b
+}
+
+fn rt() -> tokio::runtime::Runtime {
+    tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap()
+}
+
+fn router() -> Router {
+    let security = Security::new().with_allowed_origin(APP_ORIGIN);
+    Router::new(commands!(add), Config::new().with_security(security))
+}
+
+fn request(headers: &[(header::HeaderName, &str)]) -> Request<Vec<u8>> {
+    let mut request = Request::post(command_to_url("add"));
+    for (name, value) in headers {
+        request = request.header(name, *value);
+    }
+    request
+        .body(tauriless::serialize_to_vec_u8(&(1, 2)).unwrap())
+        .unwrap()
+}
+
+fn allowed_origin(response: &Response<Vec<u8>>) -> Option<&str> {
+    response
+        .headers()
+        .get(header::ACCESS_CONTROL_ALLOW_ORIGIN)
+        .map(|origin| origin.to_str().unwrap())
+}
+
+#[test]
+fn any_origin_is_allowed_by_default() {
+    let rt = rt();
+    let router = Router::new(commands!(add), Config::new());
+    let response = rt.block_on(router.handle(request(&[(header::ORIGIN, "https://example.com")])));
+    assert_eq!(response.status(), 200);
+    assert_eq!(allowed_origin(&response), Some("*"));
+}
+
+#[test]
+fn allowed_origins_are_echoed_back() {
+    let rt = rt();
+    let router = router();
+
+    let response = rt.block_on(router.handle(request(&[(header::ORIGIN, APP_ORIGIN)])));
+    assert_eq!(response.status(), 200);
+    assert_eq!(allowed_origin(&response), Some(APP_ORIGIN));
+
+    // The origin is taken from the `Referer` header when there's no `Origin` header.
+    let referer = format!("{APP_ORIGIN}/settings?tab=general");
+    let response = rt.block_on(router.handle(request(&[(header::REFERER, &referer)])));
+    assert_eq!(response.status(), 200);
+    assert_eq!(allowed_origin(&response), Some(APP_ORIGIN));
+}
+
+#[test]
+fn other_origins_are_forbidden() {
+    let rt = rt();
+    let router = router();
+
+    for headers in [
+        &[(header::ORIGIN, "https://example.com")][..],
+        &[(header::ORIGIN, "null")][..],
+        &[(header::REFERER, "https://example.com/")][..],
+        &[],
+    ] {
+        let response = rt.block_on(router.handle(request(headers)));
+        assert_eq!(response.status(), 403);
+        // The rejection is readable by the page, but it doesn't reveal the allowed origin.
+        assert_eq!(allowed_origin(&response), Some("*"));
+    }
+}
+
+#[test]
+fn test_client_sends_the_origin() {
+    let rt = rt();
+    let client = TestClient::from_router(router());
+    assert!(matches!(
+        rt.block_on(client.invoke("add", serde_json::json!([1, 2]))),
+        Err(InvokeError::Status { status, .. }) if status == 403
+    ));
+
+    let client = client.with_origin(APP_ORIGIN);
+    let sum = rt
+        .block_on(client.invoke("add", serde_json::json!([1, 2])))
+        .unwrap();
+    assert_eq!(sum, serde_json::json!(3));
+}
+
+fn authenticated_router() -> Router {
+    let security = Security::new().with_auth_token();
+    Router::new(commands!(add), Config::new().with_security(security))
+}
+
+#[test]
+fn auth_tokens_are_random() {
+    let a = Security::new().with_auth_token();
+    let b = Security::new().with_auth_token();
+    assert_eq!(a.auth_token().unwrap().len(), 64);
+    assert_ne!(a.auth_token(), b.auth_token());
+    assert!(!format!("{a:?}").contains(a.auth_token().unwrap()));
+}
+
+#[test]
+fn requests_without_the_auth_token_are_unauthorized() {
+    let rt = rt();
+    let security = Security::new().with_auth_token();
+    let token = security.auth_token().unwrap().to_string();
+    let router = Router::new(commands!(add), Config::new().with_security(security));
+    let token_header = header::HeaderName::from_static(AUTH_TOKEN_HEADER);
+
+    let response = rt.block_on(router.handle(request(&[])));
+    assert_eq!(response.status(), 401);
+
+    let response = rt.block_on(router.handle(request(&[(token_header.clone(), "forged")])));
+    assert_eq!(response.status(), 401);
+
+    let response = rt.block_on(router.handle(request(&[(token_header, &token)])));
+    assert_eq!(response.status(), 200);
+}
+
+#[test]
+fn preflight_requests_allow_the_auth_token() {
+    let rt = rt();
+    let router = authenticated_router();
+    let request = Request::options(command_to_url("add"))
+        .body(Vec::new())
+        .unwrap();
+    let response = rt.block_on(router.handle(request));
+    assert_eq!(response.status(), 204);
+    assert_eq!(
+        response.headers()[header::ACCESS_CONTROL_ALLOW_HEADERS],
+        AUTH_TOKEN_HEADER
+    );
+}
+
+#[test]
+fn test_client_sends_the_auth_token() {
+    let rt = rt();
+    let client = TestClient::from_router(authenticated_router());
+    let sum = rt
+        .block_on(client.invoke("add", serde_json::json!([1, 2])))
+        .unwrap();
+    assert_eq!(sum, serde_json::json!(3));
+}
@@ -0,0 +1,64 @@
+use std::sync::mpsc;
+
+use tauriless::{Command, Config, StateManager, TokioSpawner};
+use tauriless_macro::command;
+
+struct Multiplier(i32);
+
+#[command]
+async fn multiply(n: i32, multiplier: tauriless::State<Multiplier>) -> i32 {
+    n * multiplier.0
+}
+
+#[test]
+fn tokio_spawner_does_not_require_runtime_context() {
+    let rt = tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(1)
+        .build()
+        .unwrap();
+    // The runtime context is not entered on this thread.
+    assert!(tokio::runtime::Handle::try_current().is_err());
+
+    let config = std::sync::Arc::new(
+        Config::new()
+            .with_state(StateManager::new().with(Multiplier(2)))
+            .with_spawner(TokioSpawner::new(rt.handle().clone())),
+    );
+
+    let (tx, rx) = mpsc::channel::<i32>();
+    let config_clone = config.clone();
+    config.spawner().spawn(Box::pin(async move {
+        let ret = <__command_multiply as Command>::async_command(
+            (21,),
+            config_clone.state(),
+            &tauriless::CancellationToken::new(),
+        )
+        .await;
+        tx.send(ret).unwrap();
+    }));
+    assert_eq!(rx.recv().unwrap(), 42);
+
+    let (tx, rx) = mpsc::channel::<i32>();
+    config
+        .spawner()
+        .spawn_blocking(Box::new(move || tx.send(42).unwrap()));
+    assert_eq!(rx.recv().unwrap(), 42);
+}
+
+#[test]
+fn tokio_spawner_sleeps_on_runtime_timer() {
+    let rt = tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(1)
+        .enable_time()
+        .build()
+        .unwrap();
+    let config = Config::new().with_spawner(TokioSpawner::new(rt.handle().clone()));
+    // The sleep is created outside of the runtime context but completes on the runtime.
+    let sleep = config.spawner().sleep(std::time::Duration::from_millis(10));
+    let (tx, rx) = mpsc::channel::<()>();
+    config.spawner().spawn(Box::pin(async move {
+        sleep.await;
+        tx.send(()).unwrap();
+    }));
+    rx.recv_timeout(std::time::Duration::from_secs(5)).unwrap();
+}
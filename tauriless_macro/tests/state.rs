@@ -0,0 +1,97 @@
+use std::sync::atomic::{AtomicI32, Ordering};
+
+use tauriless::{Command, State, StateManager};
+use tauriless_macro::command;
+
+struct Multiplier(i32);
+
+#[derive(Default)]
+struct Counter(AtomicI32);
+
+#[command]
+fn multiply(n: i32, multiplier: State<Multiplier>) -> i32 {
+    n * multiplier.0
+}
+
+#[command]
+fn increment(counter: tauriless::State<Counter>) -> i32 {
+    counter.0.fetch_add(1, Ordering::Relaxed) + 1
+}
+
+#[command]
+async fn multiply_async(multiplier: State<Multiplier>, n: i32) -> i32 {
+    n * multiplier.0
+}
+
+#[test]
+fn state_args_are_not_part_of_args() {
+    let _: <__command_multiply as Command>::Args = (1i32,);
+    let _: <__command_increment as Command>::Args = ();
+    let _: <__command_multiply_async as Command>::Args = (1i32,);
+}
+
+#[test]
+fn sync_commands_receive_managed_state() {
+    let state = StateManager::new()
+        .with(Multiplier(3))
+        .with(Counter::default());
+
+    assert_eq!(
+        <__command_multiply as Command>::sync_command(
+            (2,),
+            &state,
+            &tauriless::CancellationToken::new()
+        ),
+        6
+    );
+    assert_eq!(
+        <__command_increment as Command>::sync_command(
+            (),
+            &state,
+            &tauriless::CancellationToken::new()
+        ),
+        1
+    );
+    assert_eq!(
+        <__command_increment as Command>::sync_command(
+            (),
+            &state,
+            &tauriless::CancellationToken::new()
+        ),
+        2
+    );
+}
+
+#[test]
+fn async_commands_receive_managed_state() {
+    let rt = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+    let state = StateManager::new().with(Multiplier(5));
+
+    let ret = rt.block_on(<__command_multiply_async as Command>::async_command(
+        (2,),
+        &state,
+        &tauriless::CancellationToken::new(),
+    ));
+    assert_eq!(ret, 10);
+}
+
+#[test]
+fn managing_the_same_type_twice_keeps_the_first_value() {
+    let mut state = StateManager::new();
+    assert!(state.manage(Multiplier(1)));
+    assert!(!state.manage(Multiplier(2)));
+    assert_eq!(state.get::<Multiplier>().0, 1);
+}
+
+#[test]
+#[should_panic(expected = "is not managed")]
+fn unmanaged_state_panics() {
+    let state = StateManager::new();
+    <__command_multiply as Command>::sync_command(
+        (2,),
+        &state,
+        &tauriless::CancellationToken::new(),
+    );
+}
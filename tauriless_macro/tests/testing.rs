@@ -0,0 +1,110 @@
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tauriless::testing::{InvokeError, TestClient};
+use tauriless::{Config, TokioSpawner};
+use tauriless_macro::{command, commands};
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+#[command]
+fn translate(point: Point, dx: i32, dy: Option<i32>) -> Point {
+    Point {
+        x: point.x + dx,
+        y: point.y + dy.unwrap_or(0),
+    }
+}
+
+#[command]
+fn parse(s: String) -> Result<u8, String> {
+    s.parse().map_err(|_| format!("not a u8: {s}"))
+}
+
+#[command]
+fn argsless() -> &'static str {
+    "no args"
+}
+
+#[command]
+fn panics() {
+    panic!("panics");
+}
+
+#[command]
+async fn async_sum(numbers: Vec<i64>) -> i64 {
+    numbers.into_iter().sum()
+}
+
+#[command(timeout = "10ms")]
+async fn never_returns() {
+    std::future::pending::<()>().await;
+}
+
+fn client() -> (tokio::runtime::Runtime, TestClient) {
+    let rt = tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+    let config = Config::new().with_spawner(TokioSpawner::new(rt.handle().clone()));
+    let client = TestClient::new(
+        commands!(translate, parse, argsless, panics, async_sum, never_returns),
+        config,
+    );
+    (rt, client)
+}
+
+#[test]
+fn return_values_are_decoded() {
+    let (rt, client) = client();
+    rt.block_on(async {
+        let point = json!({ "x": 1, "y": 2 });
+        let ret = client.invoke("translate", json!([point, 1])).await.unwrap();
+        assert_eq!(ret, json!({ "x": 2, "y": 2 }));
+
+        let ret: Point = client
+            .invoke_as("translate", json!({ "point": point, "dx": 1, "dy": 1 }))
+            .await
+            .unwrap();
+        assert_eq!(ret, Point { x: 2, y: 3 });
+
+        let ret = client
+            .invoke("argsless", serde_json::Value::Null)
+            .await
+            .unwrap();
+        assert_eq!(ret, json!("no args"));
+
+        let ret = client
+            .invoke("async_sum", json!([[1, 2, 3]]))
+            .await
+            .unwrap();
+        assert_eq!(ret, json!(6));
+    });
+}
+
+#[test]
+fn errors_are_decoded() {
+    let (rt, client) = client();
+    rt.block_on(async {
+        match client.invoke("parse", json!(["256"])).await {
+            Err(InvokeError::Command(err)) => assert_eq!(err, json!("not a u8: 256")),
+            other => panic!("unexpected result: {other:?}"),
+        }
+        assert!(matches!(
+            client.invoke("never_returns", json!([])).await,
+            Err(InvokeError::Timeout(_))
+        ));
+        for (command, args, status) in [
+            ("unknown", json!([]), 400),
+            ("parse", json!([1, 2]), 400),
+            ("panics", json!([]), 500),
+        ] {
+            match client.invoke(command, args).await {
+                Err(InvokeError::Status { status: actual, .. }) => assert_eq!(actual, status),
+                other => panic!("unexpected result of `{command}`: {other:?}"),
+            }
+        }
+    });
+}
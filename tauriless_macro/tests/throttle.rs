@@ -0,0 +1,136 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Mutex};
+use std::time::Duration;
+
+use tauriless::http::{header, Request, Response};
+use tauriless::{Command, Config, RateLimit, Router, TokioSpawner};
+use tauriless_common::url::command_to_url;
+use tauriless_macro::{command, commands};
+
+// The invocations of `gated` wait for a permit, so that they keep running until the test releases them.
+static GATE: tokio::sync::Semaphore = tokio::sync::Semaphore::const_new(0);
+
+#[command(max_concurrency = 2)]
+async fn gated() {
+    GATE.acquire().await.unwrap().forget();
+}
+
+#[command(rate_limit = "2/h")]
+fn limited() {}
+
+// The invocations of `blocking_limited` wait for the lock, so that they keep running until the test releases it.
+static BLOCKING_GATE: Mutex<()> = Mutex::new(());
+static EXPLODE: AtomicBool = AtomicBool::new(false);
+
+#[command(blocking, max_concurrency = 1, rate_limit = "5/10s")]
+fn blocking_limited() {
+    if EXPLODE.load(Ordering::SeqCst) {
+        panic!("blocking_limited exploded");
+    }
+    drop(BLOCKING_GATE.lock().unwrap());
+}
+
+fn request(command: &str) -> Request<Vec<u8>> {
+    Request::post(command_to_url(command))
+        .body(tauriless::serialize_to_vec_u8(&()).unwrap())
+        .unwrap()
+}
+
+fn router() -> (tokio::runtime::Runtime, Router) {
+    let rt = tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+    let config = Config::new().with_spawner(TokioSpawner::new(rt.handle().clone()));
+    let router = Router::new(commands!(gated, limited, blocking_limited), config);
+    (rt, router)
+}
+
+// Dispatches the request without waiting for the response.
+fn dispatch(router: &Router, command: &str) -> mpsc::Receiver<Response<Vec<u8>>> {
+    let (sender, receiver) = mpsc::channel();
+    router.dispatch(request(command), move |response| {
+        sender.send(response.map(|body| body.into_owned())).unwrap()
+    });
+    receiver
+}
+
+#[test]
+fn limits_are_set_by_the_attribute() {
+    assert_eq!(__command_gated::MAX_CONCURRENCY, Some(2));
+    assert_eq!(__command_gated::RATE_LIMIT, None);
+    assert_eq!(
+        __command_limited::RATE_LIMIT,
+        Some(RateLimit::new(2, Duration::from_secs(60 * 60)))
+    );
+    assert_eq!(__command_blocking_limited::MAX_CONCURRENCY, Some(1));
+    assert_eq!(
+        __command_blocking_limited::RATE_LIMIT,
+        Some(RateLimit::new(5, Duration::from_secs(10)))
+    );
+}
+
+#[test]
+fn excess_concurrent_invocations_are_rejected() {
+    let (_rt, router) = router();
+    let first = dispatch(&router, "gated");
+    let second = dispatch(&router, "gated");
+
+    let response = dispatch(&router, "gated").recv().unwrap();
+    assert_eq!(response.status(), 429);
+    assert!(String::from_utf8_lossy(response.body()).contains("already running 2 invocation(s)"));
+
+    // The slots are released once the invocations are answered.
+    GATE.add_permits(2);
+    assert_eq!(first.recv().unwrap().status(), 200);
+    assert_eq!(second.recv().unwrap().status(), 200);
+    GATE.add_permits(1);
+    let response = dispatch(&router, "gated").recv_timeout(Duration::from_secs(5));
+    assert_eq!(response.unwrap().status(), 200);
+}
+
+#[test]
+fn blocking_invocations_release_their_slots() {
+    let (_rt, router) = router();
+    let gate = BLOCKING_GATE.lock().unwrap();
+    let first = dispatch(&router, "blocking_limited");
+
+    let response = dispatch(&router, "blocking_limited").recv().unwrap();
+    assert_eq!(response.status(), 429);
+    assert!(String::from_utf8_lossy(response.body()).contains("already running 1 invocation(s)"));
+
+    // The slot is released once the blocking command returns.
+    drop(gate);
+    assert_eq!(first.recv().unwrap().status(), 200);
+
+    // The slot is released when the blocking command panics as well.
+    EXPLODE.store(true, Ordering::SeqCst);
+    let response = dispatch(&router, "blocking_limited").recv_timeout(Duration::from_secs(5));
+    assert_eq!(response.unwrap().status(), 500);
+    EXPLODE.store(false, Ordering::SeqCst);
+    let response = dispatch(&router, "blocking_limited").recv_timeout(Duration::from_secs(5));
+    assert_eq!(response.unwrap().status(), 200);
+}
+
+#[test]
+fn invocations_beyond_the_rate_limit_are_rejected() {
+    let (rt, router) = router();
+    for _ in 0..2 {
+        let response = rt.block_on(router.handle(request("limited")));
+        assert_eq!(response.status(), 200);
+    }
+    let response = rt.block_on(router.handle(request("limited")));
+    assert_eq!(response.status(), 429);
+    let retry_after: u64 = response.headers()[header::RETRY_AFTER]
+        .to_str()
+        .unwrap()
+        .parse()
+        .unwrap();
+    // The next token is refilled in half an hour.
+    assert!((1790..=1800).contains(&retry_after));
+
+    // The limits are per router.
+    let (rt, router) = self::router();
+    let response = rt.block_on(router.handle(request("limited")));
+    assert_eq!(response.status(), 200);
+}
@@ -0,0 +1,79 @@
+use std::time::Duration;
+
+use tauriless::{CancellationToken, Command, Config, StateManager};
+use tauriless_macro::command;
+
+#[command(timeout = "50ms")]
+async fn never_returns() -> i32 {
+    std::future::pending::<()>().await;
+    0
+}
+
+#[command(timeout = "1h")]
+async fn returns_immediately() -> i32 {
+    42
+}
+
+#[command]
+async fn without_timeout() -> i32 {
+    42
+}
+
+#[test]
+fn timeouts_are_parsed() {
+    assert_eq!(
+        <__command_never_returns as Command>::TIMEOUT,
+        Some(Duration::from_millis(50))
+    );
+    assert_eq!(
+        <__command_returns_immediately as Command>::TIMEOUT,
+        Some(Duration::from_secs(60 * 60))
+    );
+    assert_eq!(<__command_without_timeout as Command>::TIMEOUT, None);
+}
+
+#[test]
+fn default_timeout_is_overridden() {
+    let config = Config::new();
+    assert_eq!(config.timeout::<__command_without_timeout>(), None);
+
+    let config = Config::new().with_default_timeout(Duration::from_secs(5));
+    assert_eq!(
+        config.timeout::<__command_without_timeout>(),
+        Some(Duration::from_secs(5))
+    );
+    assert_eq!(
+        config.timeout::<__command_never_returns>(),
+        Some(Duration::from_millis(50))
+    );
+}
+
+#[test]
+fn commands_time_out() {
+    let rt = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+    let config = Config::new();
+    let state = StateManager::new();
+    let token = CancellationToken::new();
+
+    let ret = rt.block_on(tauriless::timeout(
+        config.sleep_for_timeout::<__command_never_returns>(),
+        <__command_never_returns as Command>::async_command((), &state, &token),
+    ));
+    assert_eq!(ret, None);
+    let response = tauriless::handle_command_timeout(
+        <__command_never_returns as Command>::NAME,
+        config.timeout::<__command_never_returns>().unwrap(),
+    );
+    assert_eq!(
+        response.status().as_u16(),
+        tauriless_common::status::COMMAND_TIMEOUT
+    );
+
+    let ret = rt.block_on(tauriless::timeout(
+        config.sleep_for_timeout::<__command_returns_immediately>(),
+        <__command_returns_immediately as Command>::async_command((), &state, &token),
+    ));
+    assert_eq!(ret, Some(42));
+}
@@ -2,23 +2,144 @@
 
 // TODO: add a streaming APIs for serialization/deserialization
 
+mod limits;
+mod shape;
+
+use shape::Shape;
+
 /// A function for `tauriless-js` to deserialize a [`wasm_bindgen::JsValue`] into a [`Vec<u8>`](Vec)
 /// that represents it.
 ///
 /// This function exists to allow the `tauriless-js` WASM module to efficiently send a [`wasm_bindgen::JsValue`]
 /// to the [custom protocol handler] in [`wry`].
 ///
+/// The encoded value must be within the default [`Limits`], just like the arguments accepted by the
+/// [custom protocol handler] by default. See [`js_value_to_vec_u8_with_limits()`].
+///
 /// [custom protocol handler]: https://docs.rs/wry/0.37.0/wry/struct.WebViewBuilder.html#method.with_custom_protocol
 /// [`wry`]: https://docs.rs/wry/0.37.0/wry/
 pub fn js_value_to_vec_u8(
     js_value: wasm_bindgen::JsValue,
+) -> Result<Vec<u8>, wasm_bindgen::JsValue> {
+    js_value_to_vec_u8_with_limits(js_value, &Limits::default())
+}
+
+/// The counterpart of [`js_value_to_vec_u8()`] for the [`Limits`] other than the default ones.
+///
+/// The limits are checked once the value is encoded, so that the value the [custom protocol handler]
+/// would reject is not sent at all.
+///
+/// [custom protocol handler]: https://docs.rs/wry/0.37.0/wry/struct.WebViewBuilder.html#method.with_custom_protocol
+pub fn js_value_to_vec_u8_with_limits(
+    js_value: wasm_bindgen::JsValue,
+    limits: &Limits,
 ) -> Result<Vec<u8>, wasm_bindgen::JsValue> {
     let deserializer: serde_wasm_bindgen::Deserializer =
         serde_wasm_bindgen::Deserializer::from(js_value);
     let value = <pot::Value<'static> as serde::Deserialize>::deserialize(deserializer)
         .map_err(|e| wasm_bindgen::JsValue::from_str(&format!("Failed to deserialize: {:?}", e)))?;
-    pot::to_vec(&value)
-        .map_err(|e| wasm_bindgen::JsValue::from_str(&format!("Failed to serialize: {:?}", e)))
+    let encoded: Vec<u8> = pot::to_vec(&value)
+        .map_err(|e| wasm_bindgen::JsValue::from_str(&format!("Failed to serialize: {:?}", e)))?;
+    check_limits(&encoded, limits)
+        .map_err(|e| wasm_bindgen::JsValue::from_str(&format!("Failed to serialize: {e}")))?;
+    Ok(encoded)
+}
+
+/// The limits of the arguments accepted by the [custom protocol handler], which protect it from the huge or
+/// deeply nested payloads sent by a hostile or buggy page.
+///
+/// See [`check_limits()`].
+///
+/// [custom protocol handler]: https://docs.rs/wry/0.37.0/wry/struct.WebViewBuilder.html#method.with_custom_protocol
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Limits {
+    /// The maximum size of the encoded arguments in bytes.
+    pub max_body_size: usize,
+    /// The maximum number of the nested sequences, maps, options, newtypes and enum variants.
+    pub max_depth: usize,
+    /// The maximum number of the elements of a sequence or the entries of a map.
+    pub max_collection_len: usize,
+}
+
+impl Limits {
+    /// The limits that accept any arguments.
+    pub const UNLIMITED: Limits = Limits {
+        max_body_size: usize::MAX,
+        max_depth: usize::MAX,
+        max_collection_len: usize::MAX,
+    };
+}
+
+impl Default for Limits {
+    /// 16 MiB of the encoded arguments, 128 levels of nesting and 1 048 576 elements per collection.
+    fn default() -> Self {
+        Self {
+            max_body_size: 16 * 1024 * 1024,
+            max_depth: 128,
+            max_collection_len: 1024 * 1024,
+        }
+    }
+}
+
+/// The dedicated module for the [`check_limits()`] function.
+pub mod check_limits {
+    /// The error type for the [`check_limits()`](super::check_limits()) function.
+    #[derive(thiserror::Error, Debug)]
+    pub enum Error {
+        /// The encoded arguments are larger than [`Limits::max_body_size`](super::Limits::max_body_size).
+        #[error("the body of {size} bytes exceeds the limit of {max} bytes")]
+        BodyTooLarge {
+            /// The size of the body in bytes.
+            size: usize,
+            /// The limit of the size.
+            max: usize,
+        },
+        /// The arguments are nested deeper than [`Limits::max_depth`](super::Limits::max_depth).
+        #[error("the arguments are nested deeper than the limit of {max} levels")]
+        TooDeep {
+            /// The limit of the depth.
+            max: usize,
+        },
+        /// A collection is longer than [`Limits::max_collection_len`](super::Limits::max_collection_len).
+        #[error("a collection exceeds the limit of {max} elements")]
+        CollectionTooLong {
+            /// The limit of the length.
+            max: usize,
+        },
+        /// The arguments are malformed, so they couldn't be checked.
+        #[error(transparent)]
+        FromSliceError(#[from] pot::Error),
+    }
+}
+
+/// A function for the [custom protocol handler] to check that the encoded arguments are within the [`Limits`]
+/// before they are deserialized.
+///
+/// The size of the body is checked first. Then the value is walked without being allocated, and the walk stops
+/// as soon as it's nested too deep or a collection is too long, whether according to its declared length or
+/// to the number of its elements.
+///
+/// [custom protocol handler]: https://docs.rs/wry/0.37.0/wry/struct.WebViewBuilder.html#method.with_custom_protocol
+pub fn check_limits(view: &[u8], limits: &Limits) -> Result<(), check_limits::Error> {
+    use check_limits::Error;
+    use serde::de::DeserializeSeed;
+
+    if view.len() > limits.max_body_size {
+        return Err(Error::BodyTooLarge {
+            size: view.len(),
+            max: limits.max_body_size,
+        });
+    }
+    let violation: std::cell::Cell<Option<Error>> = std::cell::Cell::new(None);
+    let checked: Result<(), pot::Error> = pot::de::Deserializer::from_slice(view, usize::MAX)
+        .and_then(|mut deserializer| {
+            limits::Checker::new(limits, &violation).deserialize(&mut deserializer)
+        });
+    match (checked, violation.take()) {
+        (_, Some(violation)) => Err(violation),
+        (Err(e), None) => Err(Error::FromSliceError(e)),
+        (Ok(()), None) => Ok(()),
+    }
 }
 
 /// The dedicated module for the [`slice_to_deserialize()`] function.
@@ -45,6 +166,110 @@ where
     Ok(value)
 }
 
+/// The arguments of a command as supplied by the frontend.
+///
+/// See [`slice_to_deserialize_args()`].
+pub enum PositionalOrNamed<P, N> {
+    /// The arguments were supplied as a sequence with one element per argument, or omitted altogether
+    /// for the commands without required arguments.
+    Positional(P),
+    /// The arguments were supplied as a map keyed by parameter name.
+    Named(N),
+}
+
+/// The dedicated module for the [`slice_to_deserialize_args()`] function.
+pub mod slice_to_deserialize_args {
+    /// The accepted numbers of the positional arguments of a command.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct Arity {
+        /// The number of the arguments that can't be omitted.
+        pub min: usize,
+        /// The number of all arguments.
+        pub max: usize,
+    }
+
+    impl std::fmt::Display for Arity {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            let maybe_s = if self.max == 1 { "" } else { "s" };
+            if self.min == self.max {
+                write!(f, "{max} argument{maybe_s}", max = self.max)
+            } else {
+                write!(
+                    f,
+                    "{min} to {max} arguments",
+                    min = self.min,
+                    max = self.max
+                )
+            }
+        }
+    }
+
+    /// The error type for the [`slice_to_deserialize_args()`](super::slice_to_deserialize_args()) function.
+    #[derive(thiserror::Error, Debug)]
+    pub enum Error {
+        /// The conversion from a slice to the arguments failed.
+        #[error(transparent)]
+        FromSliceError(#[from] pot::Error),
+        /// The positional arguments were supplied as an array of the wrong length.
+        #[error("expected {expected}, got {got}")]
+        ArityMismatch {
+            /// The arity of the command.
+            expected: Arity,
+            /// The length of the supplied array.
+            got: usize,
+        },
+        /// The arguments were supplied neither as an array nor as an object.
+        #[error("expected an array of {expected} or an object of named arguments")]
+        NotArguments {
+            /// The arity of the command.
+            expected: Arity,
+        },
+    }
+}
+
+/// A function for the [custom protocol handler] to deserialize a `&[u8]` into the arguments of a command
+/// supplied either positionally, as `P`, or by parameter name, as `N`.
+///
+/// The arguments are treated as named if and only if they were supplied as a map, e.g. as
+/// `invoke("cmd", { a: 1, b: 2 })` in `tauriless-js`.
+///
+/// Otherwise, the arguments must be supplied as a sequence regardless of the arity of the command,
+/// e.g. as `invoke("cmd", [1, 2])`, `invoke("cmd", [1])`, or `invoke("cmd", [])`, and the length of the sequence
+/// must be within `arity`. The trailing arguments beyond `arity.start()` can be omitted. If `arity.start()` is zero,
+/// the arguments can also be omitted altogether, e.g. as `invoke("cmd")`, in which case `P` is deserialized from a unit.
+///
+/// [custom protocol handler]: https://docs.rs/wry/0.37.0/wry/struct.WebViewBuilder.html#method.with_custom_protocol
+pub fn slice_to_deserialize_args<'a, P, N>(
+    view: &'a [u8],
+    arity: std::ops::RangeInclusive<usize>,
+) -> Result<PositionalOrNamed<P, N>, slice_to_deserialize_args::Error>
+where
+    P: serde::Deserialize<'a>,
+    N: serde::Deserialize<'a>,
+{
+    use slice_to_deserialize_args::{Arity, Error};
+
+    let expected = Arity {
+        min: *arity.start(),
+        max: *arity.end(),
+    };
+    // Peeking at the shape first, rather than trying one form and then the other, keeps the error
+    // of the form that was actually used.
+    let args = match pot::from_slice::<Shape>(view)? {
+        Shape::Map => PositionalOrNamed::Named(pot::from_slice(view)?),
+        Shape::Seq(len) if !arity.contains(&len) => {
+            return Err(Error::ArityMismatch { expected, got: len })
+        }
+        Shape::Seq(_) => PositionalOrNamed::Positional(pot::from_slice(view)?),
+        Shape::Unit if expected.min == 0 => {
+            let unit = serde::de::value::UnitDeserializer::<pot::Error>::new();
+            PositionalOrNamed::Positional(P::deserialize(unit)?)
+        }
+        Shape::Unit | Shape::Other => return Err(Error::NotArguments { expected }),
+    };
+    Ok(args)
+}
+
 /// The dedicated module for the [`serialize_to_vec_u8()`] function.
 pub mod serialize_to_vec_u8 {
     /// The error type for the [`serialize_to_vec_u8()`](super::serialize_to_vec_u8()) function.
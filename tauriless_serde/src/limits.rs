@@ -0,0 +1,167 @@
+use std::cell::Cell;
+
+use serde::de::{DeserializeSeed, Deserializer, EnumAccess, MapAccess, SeqAccess, VariantAccess};
+
+use crate::check_limits::Error;
+use crate::Limits;
+
+/// The seed walking a serialized value without allocating it, and failing as soon as the value
+/// exceeds the [`Limits`].
+///
+/// The violated limit is recorded in `violation` because the error of the deserializer can only carry a message.
+#[derive(Clone, Copy)]
+pub(crate) struct Checker<'a> {
+    limits: &'a Limits,
+    // The number of the collections enclosing the value.
+    depth: usize,
+    violation: &'a Cell<Option<Error>>,
+}
+
+impl<'a> Checker<'a> {
+    pub(crate) fn new(limits: &'a Limits, violation: &'a Cell<Option<Error>>) -> Self {
+        Self {
+            limits,
+            depth: 0,
+            violation,
+        }
+    }
+
+    fn violate<E: serde::de::Error>(&self, violation: Error) -> E {
+        let e = E::custom(&violation);
+        self.violation.set(Some(violation));
+        e
+    }
+
+    // Returns the seed for the elements of the collection the value is.
+    fn nested<E: serde::de::Error>(self) -> Result<Self, E> {
+        if self.depth >= self.limits.max_depth {
+            return Err(self.violate(Error::TooDeep {
+                max: self.limits.max_depth,
+            }));
+        }
+        Ok(Self {
+            depth: self.depth + 1,
+            ..self
+        })
+    }
+
+    fn check_len<E: serde::de::Error>(&self, len: usize) -> Result<(), E> {
+        if len > self.limits.max_collection_len {
+            return Err(self.violate(Error::CollectionTooLong {
+                max: self.limits.max_collection_len,
+            }));
+        }
+        Ok(())
+    }
+}
+
+impl<'de> DeserializeSeed<'de> for Checker<'_> {
+    type Value = ();
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'de> serde::de::Visitor<'de> for Checker<'_> {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("any value")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let element: Self = self.nested()?;
+        // The declared length is checked before any of the elements is read.
+        self.check_len(seq.size_hint().unwrap_or(0))?;
+        let mut len: usize = 0;
+        while seq.next_element_seed(element)?.is_some() {
+            len += 1;
+            self.check_len(len)?;
+        }
+        Ok(())
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let entry: Self = self.nested()?;
+        self.check_len(map.size_hint().unwrap_or(0))?;
+        let mut len: usize = 0;
+        while map.next_entry_seed(entry, entry)?.is_some() {
+            len += 1;
+            self.check_len(len)?;
+        }
+        Ok(())
+    }
+
+    fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        self.nested()?.deserialize(deserializer)
+    }
+
+    fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        self.nested()?.deserialize(deserializer)
+    }
+
+    fn visit_enum<A>(self, data: A) -> Result<Self::Value, A::Error>
+    where
+        A: EnumAccess<'de>,
+    {
+        let content: Self = self.nested()?;
+        let ((), variant) = data.variant_seed(content)?;
+        variant.newtype_variant_seed(content)
+    }
+
+    fn visit_bool<E>(self, _v: bool) -> Result<Self::Value, E> {
+        Ok(())
+    }
+
+    fn visit_i64<E>(self, _v: i64) -> Result<Self::Value, E> {
+        Ok(())
+    }
+
+    fn visit_i128<E>(self, _v: i128) -> Result<Self::Value, E> {
+        Ok(())
+    }
+
+    fn visit_u64<E>(self, _v: u64) -> Result<Self::Value, E> {
+        Ok(())
+    }
+
+    fn visit_u128<E>(self, _v: u128) -> Result<Self::Value, E> {
+        Ok(())
+    }
+
+    fn visit_f64<E>(self, _v: f64) -> Result<Self::Value, E> {
+        Ok(())
+    }
+
+    fn visit_str<E>(self, _v: &str) -> Result<Self::Value, E> {
+        Ok(())
+    }
+
+    fn visit_bytes<E>(self, _v: &[u8]) -> Result<Self::Value, E> {
+        Ok(())
+    }
+
+    fn visit_none<E>(self) -> Result<Self::Value, E> {
+        Ok(())
+    }
+
+    fn visit_unit<E>(self) -> Result<Self::Value, E> {
+        Ok(())
+    }
+}
@@ -0,0 +1,76 @@
+use tauriless_serde::{check_limits, check_limits::Error, serialize_to_vec_u8, Limits};
+
+fn limits(max_body_size: usize, max_depth: usize, max_collection_len: usize) -> Limits {
+    Limits {
+        max_body_size,
+        max_depth,
+        max_collection_len,
+    }
+}
+
+// The vector nested the given number of times, e.g. `[[[1]]]` for three.
+fn nested(depth: usize) -> serde_json::Value {
+    (0..depth).fold(serde_json::json!(1), |value, _| serde_json::json!([value]))
+}
+
+#[test]
+fn arguments_within_the_limits_are_accepted() {
+    let args =
+        serialize_to_vec_u8(&(1, "two", vec![3, 4], serde_json::json!({ "five": [6] }))).unwrap();
+    assert!(check_limits(&args, &Limits::default()).is_ok());
+    assert!(check_limits(&args, &limits(args.len(), 3, 4)).is_ok());
+    assert!(check_limits(&args, &Limits::UNLIMITED).is_ok());
+}
+
+#[test]
+fn large_bodies_are_rejected() {
+    let args = serialize_to_vec_u8(&("a".repeat(100),)).unwrap();
+    let max_body_size: usize = args.len() - 1;
+    assert!(matches!(
+        check_limits(&args, &limits(max_body_size, 128, 128)),
+        Err(Error::BodyTooLarge { size, max }) if size == args.len() && max == max_body_size
+    ));
+}
+
+#[test]
+fn deeply_nested_arguments_are_rejected() {
+    let within = serialize_to_vec_u8(&nested(8)).unwrap();
+    assert!(check_limits(&within, &limits(usize::MAX, 8, 128)).is_ok());
+
+    let beyond = serialize_to_vec_u8(&nested(9)).unwrap();
+    assert!(matches!(
+        check_limits(&beyond, &limits(usize::MAX, 8, 128)),
+        Err(Error::TooDeep { max: 8 })
+    ));
+
+    let map = serialize_to_vec_u8(&serde_json::json!({ "a": { "b": { "c": 1 } } })).unwrap();
+    assert!(matches!(
+        check_limits(&map, &limits(usize::MAX, 2, 128)),
+        Err(Error::TooDeep { max: 2 })
+    ));
+}
+
+#[test]
+fn long_collections_are_rejected() {
+    let seq = serialize_to_vec_u8(&(vec![0u8; 17],)).unwrap();
+    assert!(check_limits(&seq, &limits(usize::MAX, 128, 17)).is_ok());
+    assert!(matches!(
+        check_limits(&seq, &limits(usize::MAX, 128, 16)),
+        Err(Error::CollectionTooLong { max: 16 })
+    ));
+
+    let map: std::collections::BTreeMap<String, u8> = (0..17).map(|i| (i.to_string(), i)).collect();
+    let map = serialize_to_vec_u8(&map).unwrap();
+    assert!(matches!(
+        check_limits(&map, &limits(usize::MAX, 128, 16)),
+        Err(Error::CollectionTooLong { max: 16 })
+    ));
+}
+
+#[test]
+fn malformed_arguments_are_reported() {
+    assert!(matches!(
+        check_limits(b"\xff\x00", &Limits::default()),
+        Err(Error::FromSliceError(_))
+    ));
+}